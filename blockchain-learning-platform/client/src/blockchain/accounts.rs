@@ -0,0 +1,40 @@
+// client/src/blockchain/accounts.rs
+
+use blockchain_common::accounts::AccountState;
+
+use super::blockchain_db::BlockChainDB;
+
+/// 체인을 재생한 잔액을 담는 RocksDB 키 (JSON)
+pub(super) const ACCOUNTS_KEY: &str = "account_state";
+
+impl BlockChainDB {
+    /// 저장된 `chain_id` 체인의 잔액 (체인 끝까지 반영되어 있지 않거나 읽을 수 없으면 체인을 다시 재생)
+    pub fn load_accounts(&self, chain_id: u32) -> AccountState {
+        let stored = self
+            .raw_get(ACCOUNTS_KEY)
+            .and_then(|value| serde_json::from_slice::<AccountState>(&value).ok());
+        match stored {
+            Some(state) if state.chain_id == chain_id && Some(state.height) == self.load_latest_index() => state,
+            _ => self.rebuild_accounts(chain_id),
+        }
+    }
+
+    /// 제네시스부터 체인을 재생해 잔액을 다시 계산하고 저장
+    /// 규칙을 어긴 블록이 있으면 그 앞 블록까지만 반영
+    pub fn rebuild_accounts(&self, chain_id: u32) -> AccountState {
+        let mut state = AccountState::new(chain_id);
+        for block in self.load_all_blocks().iter().filter(|block| block.index > 0) {
+            if let Err(e) = state.apply_block(block) {
+                eprintln!("Stopped replaying balances at block {}: {}", block.index, e);
+                break;
+            }
+        }
+        self.save_accounts(&state);
+        state
+    }
+
+    pub fn save_accounts(&self, state: &AccountState) {
+        let value = serde_json::to_vec(state).expect("잔액 직렬화 실패");
+        self.raw_put(ACCOUNTS_KEY, &value);
+    }
+}
@@ -1,7 +1,8 @@
 // client/src/blockchain.rs
 use crate::blockchain::blockchain_db::{Block, BlockChainDB};
+use blockchain_common::chain;
 
-use super::blockchain_db::Problem;
+use super::blockchain_db::{BlockBody, Problem};
 
 pub struct BlockChain {
     db: BlockChainDB,
@@ -12,25 +13,9 @@ impl BlockChain {
     pub fn new(db_path: &str) -> Self {
         let db = BlockChainDB::new(db_path);
 
-        let problem3 = Problem {
-            matrix: vec![
-                vec![1, 2, 3, 4],
-                vec![5, 6, 7, 8],
-                vec![9, 10, 11, 12],
-                vec![13, 14, 15, 16],
-            ],
-        };
-
         // 블록체인 초기화 및 제네시스 블록 생성
         if db.load_latest_index().is_none() {
-            let genesis_block = Block::new(
-                0,
-                problem3,                         // 문제는 비어 있음
-                vec![],                         // 풀이도 비어 있음
-                vec![],                         // 이전 블록 풀이도 없음
-                "GenesisNode".to_string(),      // 제네시스 블록 생성 노드 ID
-                "Genesis Block".to_string(),    // 제네시스 블록 데이터
-            );
+            let genesis_block = Block::genesis(); // 모든 노드가 같은 제네시스 블록
             db.save_block(&genesis_block);
             db.save_latest_index(genesis_block.index);
         }
@@ -52,16 +37,18 @@ impl BlockChain {
         problem: Problem,
         solution: Vec<Vec<u32>>,
         node_id: String,
-        data: String,
+        body: BlockBody,
     ) {
         let latest_block = self.get_latest_block();
         let new_block = Block::new(
             latest_block.index + 1,       // 새 블록의 인덱스는 이전 블록의 인덱스 + 1
+            latest_block.hash.clone(),    // 이전 블록의 해시로 연결
+            chain::now_millis(),          // 현재 시각 (UTC, 밀리초)
             problem,                      // 새로운 문제
             solution,                     // 새로운 풀이
             latest_block.solution.clone(), // 이전 블록의 풀이를 참조
             node_id,                      // 블록 생성 노드 ID
-            data,                         // 거래 내역
+            body,                         // 코인베이스 보상과 거래 내역
         );
 
         self.db.save_block(&new_block);
@@ -1,88 +1,83 @@
 // client/src/blockchain/blockchain_db.rs
 use rocksdb::{DB, Options};
-use serde::{Serialize, Deserialize};
 use bincode;
-use chrono::{DateTime, TimeZone, Utc, FixedOffset};
-
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Block {
-    pub index: u64,
-    pub timestamp: String,
-    pub problem: Problem,      // 숫자 배열
-    pub solution: Vec<Vec<u32>>,     // 숫자 배열
-    pub prev_solution: Vec<Vec<u32>>,
-    pub node_id: String,
-    pub data: String,
-}
 
-#[derive(Debug,Clone, Serialize, Deserialize )]
-pub struct Problem {
-    pub matrix: Vec<Vec<u32>>,
-}
+use blockchain_common::identity::{self, SigningKey};
 
-impl Block {
-    pub fn new(
-        index: u64,
-        problem: Problem,
-        solution: Vec<Vec<u32>>,
-        prev_solution: Vec<Vec<u32>>,
-        node_id: String,
-        data: String,
-    ) -> Self {
-        let sys_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_secs();
-        let datetime: DateTime<Utc> = Utc.timestamp_opt(sys_timestamp as i64, 0)
-            .single()
-            .expect("Invalid timestamp");
-
-        // 한국 시간으로 변환 (UTC+9)
-        let kst_offset = FixedOffset::east_opt(9 * 3600)
-            .expect("Invalid offset");
-        let kst_datetime = datetime.with_timezone(&kst_offset);
-        
-        let timestamp= kst_datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-
-        Block {
-            index,
-            timestamp,
-            problem,
-            solution,
-            prev_solution,
-            node_id,
-            data,
-        }
-    }
-}
+use super::accounts::ACCOUNTS_KEY;
+use super::migrate::decode_block;
+use super::utxo::UTXO_CF;
+
+// 블록과 문제 모양은 서버와 같은 정의를 씀 (체인이 정한 문제를 클라이언트가 다시 만들어 확인)
+pub use blockchain_common::protocol::{Block, BlockBody, Board, Cage, Coinbase, Problem, Round, Transaction};
 
 pub struct BlockChainDB {
     db: DB,
 }
 
+/// 노드 키를 저장하는 RocksDB 키
+const NODE_KEY: &str = "node_signing_key";
+
 impl BlockChainDB {
     pub fn new(db_path: &str) -> Self {
         let mut options = Options::default();
         options.create_if_missing(true);
-        let db = DB::open(&options, db_path).expect("RocksDB 초기화 실패");
+        // UTXO 원장의 쓰지 않은 출력은 블록 옆의 별도 컬럼 패밀리에 저장
+        options.create_missing_column_families(true);
+        let db = DB::open_cf(&options, db_path, [UTXO_CF]).expect("RocksDB 초기화 실패");
         BlockChainDB { db }
     }
 
     pub fn save_block(&self, block: &Block) {
         let key = format!("block_{:08}", block.index);
-        let value = bincode::serialize(block).expect("블록 직렬화 실패");
+        // `Problem`은 태그가 붙은 열거형이라 bincode 로는 다시 읽을 수 없어 JSON 으로 저장
+        let value = serde_json::to_vec(block).expect("블록 직렬화 실패");
         self.db.put(key.as_bytes(), value).expect("블록 저장 실패");
     }
 
     pub fn load_block(&self, index: u64) -> Option<Block> {
+        self.read_block(index).ok().flatten()
+    }
+
+    /// 블록을 읽되, 없는 블록(`Ok(None)`)과 읽거나 디코딩하지 못한 블록(`Err`)을 구분
+    pub fn read_block(&self, index: u64) -> Result<Option<Block>, Box<dyn std::error::Error>> {
         let key = format!("block_{:08}", index);
-        match self.db.get(key.as_bytes()) {
-            Ok(Some(value)) => bincode::deserialize(&value).ok(),
-            _ => None,
+        match self.db.get(key.as_bytes())? {
+            Some(value) => Ok(Some(decode_block(&value)?)),
+            None => Ok(None),
         }
     }
 
+    /// 저장된 블록 바이트 그대로 (형식 변환용)
+    pub(super) fn raw_block(&self, index: u64) -> Option<Vec<u8>> {
+        self.raw_get(&format!("block_{:08}", index))
+    }
+
+    pub(super) fn db(&self) -> &DB {
+        &self.db
+    }
+
+    pub(super) fn raw_get(&self, key: &str) -> Option<Vec<u8>> {
+        self.db.get(key).ok().flatten()
+    }
+
+    pub(super) fn raw_put(&self, key: &str, value: &[u8]) {
+        self.db.put(key, value).expect("DB 저장 실패");
+    }
+
+    /// 이 노드의 Ed25519 키 (처음 실행할 때 만들어 저장, `reset_db` 로도 지워지지 않음)
+    pub fn load_or_create_key(&self) -> SigningKey {
+        if let Ok(Some(value)) = self.db.get(NODE_KEY) {
+            if let Ok(bytes) = <[u8; 32]>::try_from(value.as_slice()) {
+                return SigningKey::from_bytes(&bytes);
+            }
+            eprintln!("Stored node key is malformed, generating a new one");
+        }
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        self.db.put(NODE_KEY, key.to_bytes()).expect("노드 키 저장 실패");
+        key
+    }
+
     pub fn save_latest_index(&self, index: u64) {
         let value = bincode::serialize(&index).expect("인덱스 직렬화 실패");
         self.db.put("latest_block_index", value).expect("최신 블록 인덱스 저장 실패");
@@ -101,8 +96,10 @@ impl BlockChainDB {
         for item in self.db.iterator(rocksdb::IteratorMode::Start) {
             if let Ok((key, value)) = item {
                 if key.starts_with(b"block_") {
-                    if let Ok(block) = bincode::deserialize::<Block>(&value) {
-                        blocks.push(block);
+                    match decode_block(&value) {
+                        Ok(block) => blocks.push(block),
+                        // 건너뛴 블록은 `verify_chain` 으로 어디서 끊겼는지 확인할 수 있음
+                        Err(e) => eprintln!("Skipping undecodable block {}: {}", String::from_utf8_lossy(&key), e),
                     }
                 }
             }
@@ -122,22 +119,29 @@ impl BlockChainDB {
                 }
             }
         }
-        // latest_block_index 삭제
+        // latest_block_index 와 재생한 원장(잔액, 쓰지 않은 출력) 삭제
+        // 원장 모델(`LEDGER_MODEL_KEY`)은 서버가 정하므로 남겨 둠
         batch.delete(b"latest_block_index");
+        batch.delete(ACCOUNTS_KEY);
+        self.clear_utxos(&mut batch);
 
         // 일괄 적용
         self.db.write(batch).expect("DB 초기화 실패");
 
         // 2) 제네시스 블록 추가
-        let genesis_block = Block::new(
-            0,
-            Problem{matrix:vec![]},
-            vec![],
-            vec![],
-            "GenesisNode".into(),
-            "Genesis Block".into(),
-        );
+        let genesis_block = Block::genesis();
         self.save_block(&genesis_block);
         self.save_latest_index(0);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::BlockChainDB;
+
+    /// 테스트마다 따로 여는 빈 DB (임시 디렉터리 아래 테스트 이름으로)
+    pub(crate) fn temp_db(name: &str) -> BlockChainDB {
+        let path = std::env::temp_dir().join(format!("blockchain-client-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&path);
+        BlockChainDB::new(path.to_str().expect("temp path is UTF-8"))
+    }
+}
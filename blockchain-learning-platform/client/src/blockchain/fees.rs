@@ -0,0 +1,58 @@
+// client/src/blockchain/fees.rs
+
+use super::blockchain_db::Block;
+
+/// 수수료를 추정할 때 보는 최근 블록 수
+pub const FEE_ESTIMATE_BLOCKS: usize = 10;
+
+/// 로컬 체인의 최근 `FEE_ESTIMATE_BLOCKS`개 블록에 들어간 거래 수수료의 중앙값 (거래가 없으면 0)
+///
+/// 서버는 수수료가 큰 거래부터 블록 템플릿에 담으므로, 최근 블록에 들어간 거래만큼 내면 곧 들어갈 가능성이 크다.
+pub fn estimate_fee(blocks: &[Block]) -> u64 {
+    let mut fees: Vec<u64> = blocks
+        .iter()
+        .rev()
+        .take(FEE_ESTIMATE_BLOCKS)
+        .flat_map(|block| block.body.transactions.iter().map(|transaction| transaction.fee))
+        .collect();
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    fees[fees.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::{BlockBody, Transaction};
+    use blockchain_common::test_support::block_with_body;
+
+    /// 수수료가 `fees`인 거래를 담은 블록
+    fn block(index: u64, fees: &[u64]) -> Block {
+        let transactions = fees.iter().map(|&fee| Transaction { fee, ..Transaction::new("a", "b", 1) }).collect();
+        block_with_body(index, "miner", BlockBody::with_fees("miner", 10, transactions))
+    }
+
+    #[test]
+    fn empty_chains_suggest_no_fee() {
+        assert_eq!(estimate_fee(&[]), 0);
+        assert_eq!(estimate_fee(&[Block::genesis(), block(1, &[])]), 0);
+    }
+
+    #[test]
+    fn suggests_the_median_fee() {
+        assert_eq!(estimate_fee(&[block(1, &[5, 1]), block(2, &[9])]), 5);
+        // 짝수 개면 가운데 두 값 중 큰 쪽
+        assert_eq!(estimate_fee(&[block(1, &[1, 2, 3, 4])]), 3);
+    }
+
+    #[test]
+    fn only_recent_blocks_count() {
+        let mut blocks = vec![block(1, &[100; 20])];
+        blocks.extend((2..=FEE_ESTIMATE_BLOCKS as u64 + 1).map(|index| block(index, &[2])));
+        assert_eq!(estimate_fee(&blocks), 2);
+        blocks.truncate(FEE_ESTIMATE_BLOCKS);
+        assert_eq!(estimate_fee(&blocks), 100);
+    }
+}
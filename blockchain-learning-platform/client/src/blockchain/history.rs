@@ -0,0 +1,124 @@
+// client/src/blockchain/history.rs
+
+use super::blockchain_db::{Block, Transaction};
+
+/// 내 거래가 어디까지 갔는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxStatus {
+    /// 서버 멤풀에서 블록을 기다리는 중
+    Pending,
+    /// 로컬 체인의 이 높이 블록에 들어감
+    Confirmed(u64),
+}
+
+/// 지갑 내역의 한 줄
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub status: TxStatus,
+    pub direction: Direction,
+    pub counterparty: String, // 받는 쪽(보낸 거래) 또는 보낸 쪽(받은 거래), 블록 보상은 "(coinbase)"
+    pub amount: u64,
+    pub fee: u64, // 보낸 거래에서 내가 낸 수수료 (받은 거래는 보낸 쪽이 냄)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+    Reward, // 내가 만든 블록의 코인베이스 (보상과 수수료)
+}
+
+/// `node_id`가 보내거나 받은 거래: 멤풀에 대기 중인 거래를 먼저, 그 아래로 로컬 체인의 최근 블록부터
+pub fn wallet_history(blocks: &[Block], mempool: &[Transaction], node_id: &str) -> Vec<HistoryEntry> {
+    let pending = mempool
+        .iter()
+        .filter_map(|transaction| entry(transaction, node_id, TxStatus::Pending));
+    let confirmed = blocks.iter().rev().flat_map(|block| {
+        let status = TxStatus::Confirmed(block.index);
+        let coinbase = &block.body.coinbase;
+        let reward = (block.index > 0 && coinbase.receiver_id == node_id && coinbase.amount > 0).then(|| HistoryEntry {
+            status,
+            direction: Direction::Reward,
+            counterparty: "(coinbase)".to_string(),
+            amount: coinbase.amount,
+            fee: 0,
+        });
+        // 블록 안에서도 나중 거래가 위로
+        let transactions = block
+            .body
+            .transactions
+            .iter()
+            .rev()
+            .filter_map(move |transaction| entry(transaction, node_id, status));
+        transactions.chain(reward)
+    });
+    pending.chain(confirmed).collect()
+}
+
+/// 내가 보내거나 받은 거래면 내역 한 줄
+fn entry(transaction: &Transaction, node_id: &str, status: TxStatus) -> Option<HistoryEntry> {
+    let (direction, counterparty, fee) = if transaction.sender_id == node_id {
+        (Direction::Sent, &transaction.receiver_id, transaction.fee)
+    } else if transaction.receiver_id == node_id {
+        (Direction::Received, &transaction.sender_id, 0)
+    } else {
+        return None;
+    };
+    Some(HistoryEntry { status, direction, counterparty: counterparty.clone(), amount: transaction.amount, fee })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::BlockBody;
+    use blockchain_common::test_support::block_with_body;
+
+    fn block(index: u64, miner: &str, transactions: Vec<Transaction>) -> Block {
+        block_with_body(index, miner, BlockBody::with_fees(miner, 10, transactions))
+    }
+
+    fn paid(sender: &str, receiver: &str, amount: u64, fee: u64) -> Transaction {
+        Transaction { fee, ..Transaction::new(sender, receiver, amount) }
+    }
+
+    fn line(status: TxStatus, direction: Direction, counterparty: &str, amount: u64, fee: u64) -> HistoryEntry {
+        HistoryEntry { status, direction, counterparty: counterparty.to_string(), amount, fee }
+    }
+
+    #[test]
+    fn pending_first_then_newest_blocks_first() {
+        let blocks = [
+            Block::genesis(),
+            block(1, "me", vec![]),
+            block(2, "carol", vec![paid("me", "bob", 3, 1), paid("alice", "me", 5, 2)]),
+            block(3, "carol", vec![paid("alice", "bob", 7, 0)]),
+        ];
+        let mempool = [paid("me", "alice", 4, 2), paid("bob", "carol", 1, 0)];
+
+        assert_eq!(
+            wallet_history(&blocks, &mempool, "me"),
+            vec![
+                line(TxStatus::Pending, Direction::Sent, "alice", 4, 2),
+                line(TxStatus::Confirmed(2), Direction::Received, "alice", 5, 0),
+                line(TxStatus::Confirmed(2), Direction::Sent, "bob", 3, 1),
+                line(TxStatus::Confirmed(1), Direction::Reward, "(coinbase)", 10, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewards_include_the_fees_of_my_blocks() {
+        let blocks = [Block::genesis(), block(1, "me", vec![paid("alice", "bob", 2, 3)])];
+        assert_eq!(
+            wallet_history(&blocks, &[], "me"),
+            vec![line(TxStatus::Confirmed(1), Direction::Reward, "(coinbase)", 13, 0)]
+        );
+    }
+
+    #[test]
+    fn strangers_and_genesis_have_no_history() {
+        let blocks = [Block::genesis(), block(1, "carol", vec![paid("alice", "bob", 2, 0)])];
+        assert!(wallet_history(&blocks, &[paid("bob", "alice", 1, 0)], "me").is_empty());
+        assert!(wallet_history(&blocks[..1], &[], &Block::genesis().body.coinbase.receiver_id).is_empty());
+    }
+}
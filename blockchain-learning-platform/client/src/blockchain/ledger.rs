@@ -0,0 +1,200 @@
+// client/src/blockchain/ledger.rs
+
+use rocksdb::WriteBatch;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use blockchain_common::chain::{BlockError, IssueRules, DEFAULT_CHAIN_ID, DEFAULT_MAX_BLOCK_TRANSACTIONS};
+use blockchain_common::emission::Emission;
+use blockchain_common::ledger::{Ledger, LedgerModel};
+
+use super::accounts::ACCOUNTS_KEY;
+use super::blockchain_db::{Block, BlockChainDB, Round};
+
+/// 이 체인의 원장 모델을 담는 RocksDB 키 (서버가 라운드에 담아 알려 줌, JSON)
+pub(super) const LEDGER_MODEL_KEY: &str = "ledger_model";
+
+/// 이 체인의 아이디를 담는 RocksDB 키 (서버가 라운드에 담아 알려 줌, JSON)
+const CHAIN_ID_KEY: &str = "chain_id";
+
+/// 높이마다 적용할 합의 규칙(보상, 문제 규칙, 블록 용량)이 바뀐 기록을 담는 RocksDB 키 (JSON `RuleHistory`)
+const RULE_HISTORY_KEY: &str = "rule_history";
+
+/// 규칙 기록이 생기기 전에 지금 규칙만 하나씩 담던 키 (기록이 없는 DB 는 높이 0 부터의 규칙으로 읽음)
+const EMISSION_KEY: &str = "emission";
+const ISSUE_RULES_KEY: &str = "issue_rules";
+const MAX_BLOCK_TRANSACTIONS_KEY: &str = "max_block_transactions";
+
+/// 한 높이부터 적용되는 합의 규칙 (서버가 라운드에 담아 알려 줌)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRules {
+    pub emission: Emission,      // 코인베이스에 넣어야 하는 블록 보상 규칙
+    pub issue: IssueRules,       // 문제를 내는 규칙
+    pub max_transactions: usize, // 블록 용량: 코인베이스를 뺀 최대 거래 수
+}
+
+impl BlockRules {
+    pub fn of_round(round: &Round) -> Self {
+        BlockRules { emission: round.emission, issue: round.rules.clone(), max_transactions: round.max_transactions }
+    }
+}
+
+impl Default for BlockRules {
+    /// 서버 기본 설정과 같은 규칙 (서버에서 라운드를 받기 전)
+    fn default() -> Self {
+        BlockRules { emission: Emission::default(), issue: IssueRules::default(), max_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS }
+    }
+}
+
+/// 규칙이 바뀐 높이와 그 높이부터의 규칙 (높이 순)
+///
+/// 서버가 규칙을 바꿔도 이미 들어간 블록은 그때의 규칙으로 만들어졌으므로, 블록마다 그 높이의 규칙으로 검사한다.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleHistory(Vec<(u64, BlockRules)>);
+
+impl RuleHistory {
+    /// `height` 블록에 적용되는 규칙 (처음 기록보다 앞선 블록은 처음 기록된 규칙, 기록이 없으면 기본 규칙)
+    pub fn at(&self, height: u64) -> BlockRules {
+        self.0
+            .iter()
+            .rev()
+            .find(|(activation, _)| *activation <= height)
+            .or(self.0.first())
+            .map(|(_, rules)| rules.clone())
+            .unwrap_or_default()
+    }
+
+    /// 마지막으로 기록된 규칙
+    pub fn latest(&self) -> BlockRules {
+        self.0.last().map(|(_, rules)| rules.clone()).unwrap_or_default()
+    }
+
+    /// `height`부터 `rules`를 적용 (그 높이 이후의 기록은 대체), 바뀌었으면 `true`
+    pub fn activate(&mut self, height: u64, rules: BlockRules) -> bool {
+        let before = self.0.clone();
+        self.0.retain(|(activation, _)| *activation < height);
+        if self.0.last().map(|(_, last)| last) != Some(&rules) {
+            self.0.push((height, rules));
+        }
+        self.0 != before
+    }
+}
+
+impl BlockChainDB {
+    /// 저장된 원장 모델 (서버에서 라운드를 받기 전이면 계정 원장)
+    pub fn load_ledger_model(&self) -> LedgerModel {
+        self.raw_get(LEDGER_MODEL_KEY)
+            .and_then(|value| serde_json::from_slice(&value).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_ledger_model(&self, model: LedgerModel) {
+        let value = serde_json::to_vec(&model).expect("원장 모델 직렬화 실패");
+        self.raw_put(LEDGER_MODEL_KEY, &value);
+    }
+
+    /// 저장된 체인 아이디 (서버에서 라운드를 받기 전이면 기본 체인)
+    pub fn load_chain_id(&self) -> u32 {
+        self.raw_get(CHAIN_ID_KEY)
+            .and_then(|value| serde_json::from_slice(&value).ok())
+            .unwrap_or(DEFAULT_CHAIN_ID)
+    }
+
+    /// 체인 아이디를 바꾸면 예전 체인 아이디로 재생한 원장은 버림 (다음 `load_ledger`에서 다시 재생)
+    pub fn save_chain_id(&self, chain_id: u32) {
+        if chain_id != self.load_chain_id() {
+            self.forget_ledger();
+        }
+        let value = serde_json::to_vec(&chain_id).expect("체인 아이디 직렬화 실패");
+        self.raw_put(CHAIN_ID_KEY, &value);
+    }
+
+    /// 높이마다 적용할 합의 규칙 기록 (기록이 생기기 전의 DB 는 저장된 지금 규칙을 높이 0 부터 적용)
+    pub fn load_rule_history(&self) -> RuleHistory {
+        if let Some(history) = self.raw_get(RULE_HISTORY_KEY).and_then(|value| serde_json::from_slice(&value).ok()) {
+            return history;
+        }
+        let defaults = BlockRules::default();
+        let rules = BlockRules {
+            emission: self.load_json(EMISSION_KEY).unwrap_or(defaults.emission),
+            issue: self.load_json(ISSUE_RULES_KEY).unwrap_or(defaults.issue),
+            max_transactions: self.load_json(MAX_BLOCK_TRANSACTIONS_KEY).unwrap_or(defaults.max_transactions),
+        };
+        let mut history = RuleHistory::default();
+        history.activate(0, rules);
+        history
+    }
+
+    /// 라운드가 알려 준 규칙을 그 라운드의 높이부터 적용하도록 기록
+    pub fn save_block_rules(&self, height: u64, rules: BlockRules) {
+        let mut history = self.load_rule_history();
+        if history.activate(height, rules) {
+            let value = serde_json::to_vec(&history).expect("규칙 기록 직렬화 실패");
+            self.raw_put(RULE_HISTORY_KEY, &value);
+        }
+    }
+
+    fn load_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.raw_get(key).and_then(|value| serde_json::from_slice(&value).ok())
+    }
+
+    /// 저장된 모델과 체인 아이디의 원장 (계정 잔액 또는 UTXO 집합)
+    pub fn load_ledger(&self) -> Ledger {
+        let chain_id = self.load_chain_id();
+        match self.load_ledger_model() {
+            LedgerModel::Account => Ledger::Account(self.load_accounts(chain_id)),
+            LedgerModel::Utxo => Ledger::Utxo(self.load_utxos(chain_id)),
+        }
+    }
+
+    /// 재생해 둔 원장(잔액, 쓰지 않은 출력)을 지움 (블록은 그대로)
+    pub(super) fn forget_ledger(&self) {
+        let mut batch = WriteBatch::default();
+        batch.delete(ACCOUNTS_KEY);
+        self.clear_utxos(&mut batch);
+        self.db().write(batch).expect("원장 삭제 실패");
+    }
+
+    /// 블록을 원장에 반영한 뒤 블록과 원장을 함께 저장 (반영할 수 없으면 아무것도 저장하지 않음)
+    pub fn append_block(&self, block: &Block, ledger: &mut Ledger) -> Result<(), BlockError> {
+        match ledger {
+            Ledger::Account(accounts) => {
+                accounts.apply_block(block)?;
+                self.save_block(block);
+                self.save_latest_index(block.index);
+                self.save_accounts(accounts);
+            }
+            Ledger::Utxo(utxos) => {
+                let diff = utxos.apply_block(block)?;
+                self.save_block(block);
+                self.save_latest_index(block.index);
+                self.save_utxo_diff(&diff, block.index);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::tests::temp_db;
+
+    #[test]
+    fn stored_emission_applies_from_genesis_until_it_changes() {
+        let db = temp_db("ledger-rule-history");
+        // 규칙 기록이 생기기 전의 DB: 지금 보상 규칙만 저장돼 있음
+        let halving = Emission::Halving { initial: 50, interval: 2 };
+        db.raw_put(EMISSION_KEY, &serde_json::to_vec(&halving).unwrap());
+        let history = db.load_rule_history();
+        assert_eq!(history.at(1).emission, halving);
+        assert_eq!(history.at(1).max_transactions, DEFAULT_MAX_BLOCK_TRANSACTIONS);
+
+        let fixed = BlockRules { emission: Emission::Fixed { reward: 7 }, ..history.latest() };
+        db.save_block_rules(5, fixed.clone());
+        let history = db.load_rule_history();
+        assert_eq!(history.at(4).emission, halving);
+        assert_eq!(history.at(5), fixed);
+        assert_eq!(history.latest(), fixed);
+    }
+}
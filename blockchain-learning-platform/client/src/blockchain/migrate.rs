@@ -0,0 +1,399 @@
+// client/src/blockchain/migrate.rs
+
+use std::collections::HashMap;
+
+use chrono::{FixedOffset, NaiveDateTime};
+use serde::Deserialize;
+
+use blockchain_common::identity::{self, SigningKey};
+
+use super::blockchain_db::{Block, BlockBody, BlockChainDB, Board, Problem};
+
+/// 저장된 블록 형식의 버전을 담는 RocksDB 키
+const SCHEMA_KEY: &str = "block_schema_version";
+
+/// 1: 본문이 문자열 `data` 인 블록, 2: 코인베이스와 거래 목록(`body`)과 머클 루트,
+/// 3: 거래마다 nonce 와 체인 아이디, 4: 보내는 쪽이 서명한 거래
+const CURRENT_SCHEMA: u32 = 4;
+
+/// 본문이 자유 문자열(`data`)이던 때의 블록
+#[derive(Deserialize)]
+struct LegacyBlock {
+    index: u64,
+    #[serde(deserialize_with = "legacy_timestamp")]
+    timestamp: u64,
+    problem: Problem,
+    solution: Vec<Vec<u32>>,
+    prev_solution: Vec<Vec<u32>>,
+    node_id: String,
+    data: String,
+    #[serde(default)]
+    difficulty: Option<u32>, // 없으면 문제의 난이도를 씀
+    #[serde(default)]
+    nonce: u64,
+    #[serde(default)]
+    difficulty_target: u32,
+    #[serde(default)]
+    round_id: u64,
+}
+
+/// 시각을 한국 시간 문자열로 저장하던 블록도 있어 두 모양을 다 받음
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyTimestamp {
+    Millis(u64),
+    Kst(String),
+}
+
+fn legacy_timestamp<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match LegacyTimestamp::deserialize(deserializer)? {
+        LegacyTimestamp::Millis(millis) => Ok(millis),
+        LegacyTimestamp::Kst(text) => parse_kst(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("unreadable block timestamp {:?}", text))),
+    }
+}
+
+/// 처음 배포된 클라이언트가 bincode 로 저장하던 블록 (시각은 한국 시간 문자열, 문제는 마방진 숫자 배열)
+#[derive(Deserialize)]
+struct BaselineBlock {
+    index: u64,
+    timestamp: String,
+    problem: BaselineProblem,
+    solution: Vec<Vec<u32>>,
+    prev_solution: Vec<Vec<u32>>,
+    node_id: String,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct BaselineProblem {
+    size: usize,
+    matrix: Vec<Vec<u32>>,
+}
+
+impl BaselineBlock {
+    /// 난이도는 저장되지 않았으므로 빈 칸 수로 채움
+    fn into_legacy(self) -> Option<LegacyBlock> {
+        let difficulty = self.problem.matrix.iter().flatten().filter(|&&value| value == 0).count() as u32;
+        Some(LegacyBlock {
+            index: self.index,
+            timestamp: parse_kst(&self.timestamp)?,
+            problem: Problem::MagicSquare(Board {
+                size: self.problem.size,
+                matrix: self.problem.matrix,
+                difficulty,
+                solution_count: None,
+            }),
+            solution: self.solution,
+            prev_solution: self.prev_solution,
+            node_id: self.node_id,
+            data: self.data,
+            difficulty: Some(difficulty),
+            nonce: 0,
+            difficulty_target: 0,
+            round_id: 0,
+        })
+    }
+}
+
+/// 예전 블록 시각(`YYYY-MM-DD HH:MM:SS`, 한국 시간)을 UTC 밀리초로 바꿈
+fn parse_kst(timestamp: &str) -> Option<u64> {
+    let kst = FixedOffset::east_opt(9 * 3600)?;
+    let datetime = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    let millis = datetime.and_local_timezone(kst).single()?.timestamp_millis();
+    u64::try_from(millis).ok()
+}
+
+/// 예전 형식의 블록 바이트 (본문이 `data`인 JSON, 그다음 처음 배포된 bincode)
+fn decode_legacy(raw: &[u8]) -> Option<LegacyBlock> {
+    if let Ok(legacy) = serde_json::from_slice::<LegacyBlock>(raw) {
+        return Some(legacy);
+    }
+    bincode::deserialize::<BaselineBlock>(raw).ok()?.into_legacy()
+}
+
+/// 저장된 블록 바이트를 지금 형식으로 읽음
+///
+/// 예전 형식은 그 자리에서 바꾸므로 `prev_hash`가 비어 있다. 시작할 때 `migrate`가 다시 이어 저장한다.
+pub(super) fn decode_block(raw: &[u8]) -> Result<Block, serde_json::Error> {
+    serde_json::from_slice::<Block>(raw).or_else(|e| decode_legacy(raw).map(LegacyBlock::into_block).ok_or(e))
+}
+
+impl LegacyBlock {
+    /// 예전 클라이언트는 보상 금액을 `data`에 적었음 (`"10"`), 숫자가 아닌 글은 버림
+    fn into_block(self) -> Block {
+        let reward = self.data.trim().parse().unwrap_or(0);
+        let mut block = Block::new(
+            self.index,
+            String::new(),
+            self.timestamp,
+            self.problem,
+            self.solution,
+            self.prev_solution,
+            self.node_id.clone(),
+            BlockBody::reward(self.node_id, reward),
+        );
+        block.nonce = self.nonce;
+        if let Some(difficulty) = self.difficulty {
+            block.difficulty = difficulty;
+        }
+        block.difficulty_target = self.difficulty_target;
+        block.round_id = self.round_id;
+        block
+    }
+}
+
+impl BlockChainDB {
+    /// 예전 형식으로 저장된 블록을 지금 형식으로 바꿈 (시작할 때 한 번)
+    ///
+    /// 예전 거래에는 nonce 와 체인 아이디가 없으므로 체인 순서대로 보내는 계정마다 0, 1, 2 ... 를 매기고
+    /// 저장된 체인 아이디를 넣고, 내가 보낸 거래는 내 키로 서명한다. 다른 노드가 보낸 예전 거래에는
+    /// 서명할 수 없어 `verify_chain`이 그 블록에서 끊겼다고 알려준다. 거래 해시(UTXO 출력의 `txid`)가 바뀌므로
+    /// 뒤에서 그 출력을 쓰는 거래의 입력도 같은 차례에 새 해시로 바꾼다.
+    /// 본문이 바뀌면 머클 루트와 해시가 바뀌므로 제네시스부터 `prev_hash`를 다시 잇는다.
+    /// 내가 낸 블록은 내 키로 다시 서명하지만, 다른 노드의 블록 서명은 되살릴 수 없어
+    /// `verify_chain`이 그 블록에서 끊겼다고 알려준다.
+    /// 읽지 못한 블록이 있으면 그 앞까지만 바꾸고 형식 버전은 남기지 않아 다음 시작 때 다시 시도한다.
+    pub fn migrate(&self, key: &SigningKey) {
+        if self.load_schema_version() == Some(CURRENT_SCHEMA) {
+            return;
+        }
+        let Some(latest_index) = self.load_latest_index() else {
+            self.save_schema_version();
+            return;
+        };
+
+        let mut blocks = Vec::new();
+        let mut converted = 0;
+        let mut complete = true;
+        for index in 0..=latest_index {
+            let Some(raw) = self.raw_block(index) else {
+                eprintln!("Block {} is missing, stopping the migration there", index);
+                complete = false;
+                break;
+            };
+            if let Ok(block) = serde_json::from_slice::<Block>(&raw) {
+                blocks.push(block);
+            } else if let Some(legacy) = decode_legacy(&raw) {
+                blocks.push(legacy.into_block());
+                converted += 1;
+            } else {
+                eprintln!("Block {} is in an unknown format, stopping the migration there", index);
+                complete = false;
+                break;
+            }
+        }
+
+        let chain_id = self.load_chain_id();
+        let my_node_id = identity::node_id(key);
+        let mut nonces: HashMap<String, u64> = HashMap::new();
+        let mut txids: HashMap<String, String> = HashMap::new(); // 예전 해시 -> 새 해시
+        let (mut renumbered, mut signed) = (0, 0);
+        for transaction in blocks.iter_mut().skip(1).flat_map(|block| block.body.transactions.iter_mut()) {
+            let old_txid = hex::encode(transaction.hash());
+            for input in &mut transaction.inputs {
+                if let Some(txid) = txids.get(&input.txid) {
+                    input.txid = txid.clone();
+                }
+            }
+            let nonce = nonces.entry(transaction.sender_id.clone()).or_insert(0);
+            transaction.nonce = *nonce;
+            transaction.chain_id = chain_id;
+            *nonce += 1;
+            renumbered += 1;
+            if transaction.sender_id == my_node_id {
+                transaction.signature = identity::sign_transaction(key, transaction);
+                signed += 1;
+            }
+            txids.insert(old_txid, hex::encode(transaction.hash()));
+        }
+        if converted == 0 && renumbered == 0 {
+            if complete {
+                self.save_schema_version();
+            }
+            return;
+        }
+
+        let mut resigned = 0;
+        let mut parent_hash = None;
+        for block in &mut blocks {
+            match parent_hash.take() {
+                None => *block = Block::genesis(),
+                Some(prev_hash) => {
+                    block.prev_hash = prev_hash;
+                    block.seal();
+                    if block.node_id == my_node_id {
+                        block.signature = identity::sign_block(key, &block.header());
+                        resigned += 1;
+                    }
+                }
+            }
+            parent_hash = Some(block.hash.clone());
+            self.save_block(block);
+        }
+        // 거래 해시가 바뀌었으므로 재생해 둔 원장도 다시 만듦
+        self.forget_ledger();
+        if complete {
+            self.save_schema_version();
+        }
+        println!(
+            "Migrated {} block(s) to transaction bodies and numbered {} transaction(s) ({} block(s) and {} transaction(s) signed with this node's key)",
+            converted, renumbered, resigned, signed
+        );
+    }
+
+    fn load_schema_version(&self) -> Option<u32> {
+        self.raw_get(SCHEMA_KEY)
+            .and_then(|value| <[u8; 4]>::try_from(value.as_slice()).ok())
+            .map(u32::from_be_bytes)
+    }
+
+    fn save_schema_version(&self) {
+        self.raw_put(SCHEMA_KEY, &CURRENT_SCHEMA.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::tests::temp_db;
+    use crate::blockchain::blockchain_db::Transaction;
+    use blockchain_common::body::BLOCK_REWARD;
+    use blockchain_common::ledger::LedgerModel;
+    use blockchain_common::test_support::block_with_body;
+    use blockchain_common::utxo::OutPoint;
+    use serde::Serialize;
+
+    /// 처음 배포된 클라이언트의 블록 모양 (bincode 로 저장하던 그대로)
+    #[derive(Serialize)]
+    struct StoredBaselineBlock {
+        index: u64,
+        timestamp: String,
+        problem: StoredBaselineProblem,
+        solution: Vec<Vec<u32>>,
+        prev_solution: Vec<Vec<u32>>,
+        node_id: String,
+        data: String,
+    }
+
+    #[derive(Serialize)]
+    struct StoredBaselineProblem {
+        size: usize,
+        matrix: Vec<Vec<u32>>,
+    }
+
+    fn baseline_bytes(index: u64, node_id: &str, data: &str) -> Vec<u8> {
+        bincode::serialize(&StoredBaselineBlock {
+            index,
+            timestamp: "2025-01-01 09:00:01".to_string(),
+            problem: StoredBaselineProblem { size: 3, matrix: vec![vec![2, 7, 6], vec![9, 0, 1], vec![4, 3, 0]] },
+            solution: vec![vec![2, 7, 6], vec![9, 5, 1], vec![4, 3, 8]],
+            prev_solution: vec![],
+            node_id: node_id.to_string(),
+            data: data.to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn baseline_bincode_blocks_decode() {
+        let block = decode_block(&baseline_bytes(1, "node", "10")).unwrap();
+        assert_eq!(block.index, 1);
+        assert_eq!(block.timestamp, 1_735_689_601_000); // 2025-01-01 00:00:01 UTC
+        assert_eq!(block.difficulty, 2);
+        assert_eq!(block.problem.board().matrix[1], vec![9, 0, 1]);
+        assert_eq!(block.body.coinbase.amount, 10);
+        assert_eq!(block.body.coinbase.receiver_id, "node");
+    }
+
+    #[test]
+    fn string_timestamp_json_blocks_decode() {
+        let mut value = serde_json::to_value(Block::genesis()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("body");
+        object.remove("merkle_root");
+        object.insert("index".to_string(), 3.into());
+        object.insert("timestamp".to_string(), "2025-01-01 09:00:00".into());
+        object.insert("data".to_string(), "7".into());
+        let block = decode_block(&serde_json::to_vec(&value).unwrap()).unwrap();
+        assert_eq!(block.index, 3);
+        assert_eq!(block.timestamp, 1_735_689_600_000);
+        assert_eq!(block.body.coinbase.amount, 7);
+    }
+
+    #[test]
+    fn baseline_chains_migrate_and_relink() {
+        let db = temp_db("migrate-baseline");
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        let me = identity::node_id(&key);
+        db.raw_put("block_00000000", &baseline_bytes(0, "genesis", "Genesis Block"));
+        db.raw_put("block_00000001", &baseline_bytes(1, &me, "10"));
+        db.save_latest_index(1);
+
+        db.migrate(&key);
+        assert_eq!(db.load_schema_version(), Some(CURRENT_SCHEMA));
+        let blocks = db.load_all_blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0], Block::genesis());
+        assert_eq!(blocks[1].prev_hash, blocks[0].hash);
+        assert_eq!(blocks[1].hash, hex::encode(blocks[1].compute_hash()));
+        assert_eq!(blocks[1].body.coinbase.amount, 10);
+        assert_eq!(identity::verify_block(&blocks[1].header(), &blocks[1].signature), Ok(()));
+    }
+
+    #[test]
+    fn undecodable_blocks_leave_the_schema_unstamped() {
+        let db = temp_db("migrate-unknown");
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        db.raw_put("block_00000000", &baseline_bytes(0, "genesis", "Genesis Block"));
+        db.raw_put("block_00000001", &baseline_bytes(1, "node", "10"));
+        db.raw_put("block_00000002", b"not a block");
+        db.save_latest_index(2);
+
+        db.migrate(&key);
+        assert_eq!(db.load_schema_version(), None);
+        // 읽은 앞부분은 바뀌어 저장됨
+        let block = db.read_block(1).unwrap().unwrap();
+        assert_eq!(block.prev_hash, Block::genesis().hash);
+        assert!(db.read_block(2).is_err());
+    }
+
+    #[test]
+    fn renumbered_utxo_spends_follow_the_new_txids() {
+        let db = temp_db("migrate-utxo");
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        let me = identity::node_id(&key);
+        db.reset_db();
+        db.save_ledger_model(LedgerModel::Utxo);
+        db.save_chain_id(7);
+
+        // nonce 와 체인 아이디가 없던 때의 UTXO 체인: 보상을 쓰고, 그 거스름돈을 다시 씀
+        let coinbase = OutPoint::coinbase(1, &BlockBody::reward(&me, BLOCK_REWARD).coinbase);
+        let first = Transaction { inputs: vec![coinbase], ..Transaction::new(&me, "bob", 4) };
+        let change = OutPoint { txid: hex::encode(first.hash()), vout: 1 };
+        let second = Transaction { inputs: vec![change], ..Transaction::new(&me, "carol", 2) };
+        for (index, transactions) in [(1, vec![]), (2, vec![first]), (3, vec![second])] {
+            let body = BlockBody { transactions, ..BlockBody::reward(&me, BLOCK_REWARD) };
+            let block = block_with_body(index, &me, body);
+            db.save_block(&block);
+            db.save_latest_index(index);
+        }
+
+        db.migrate(&key);
+        let blocks = db.load_all_blocks();
+        let first = &blocks[2].body.transactions[0];
+        assert_eq!(first.chain_id, 7);
+        assert_eq!(blocks[3].body.transactions[0].inputs[0].txid, hex::encode(first.hash()));
+        let utxos = db.rebuild_utxos(7);
+        assert_eq!(utxos.height, 3);
+        assert_eq!(utxos.balance("bob"), 4);
+        assert_eq!(utxos.balance("carol"), 2);
+        assert_eq!(utxos.balance(&me), 3 * BLOCK_REWARD - 6);
+    }
+
+    #[test]
+    fn unknown_bytes_do_not_decode() {
+        assert!(decode_block(b"not a block").is_err());
+        assert_eq!(parse_kst("yesterday"), None);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod accounts;
 pub mod blockchain_db;
-pub mod blockchain;
\ No newline at end of file
+pub mod blockchain;
+pub mod fees;
+pub mod history;
+pub mod ledger;
+pub mod migrate;
+pub mod pow;
+pub mod utxo;
+pub mod verify;
@@ -0,0 +1,18 @@
+// client/src/blockchain/pow.rs
+
+// 헤더 해시 규칙은 서버와 같은 코드를 씀
+use blockchain_common::header::BlockHeader;
+pub use blockchain_common::pow::leading_zero_bits;
+
+/// nonce 를 0부터 늘려 가며 헤더 해시가 `difficulty_target` 비트 수를 만족하는 첫 nonce 를 찾음
+/// (CPU를 오래 쓰므로 `spawn_blocking` 안에서 호출)
+pub fn mine(mut header: BlockHeader<'_>) -> (u64, [u8; 32]) {
+    header.nonce = 0;
+    loop {
+        let hash = header.hash();
+        if leading_zero_bits(&hash) >= header.difficulty_target {
+            return (header.nonce, hash);
+        }
+        header.nonce = header.nonce.wrapping_add(1);
+    }
+}
@@ -0,0 +1,179 @@
+// client/src/blockchain/utxo.rs
+
+use rocksdb::{ColumnFamily, WriteBatch};
+
+use blockchain_common::utxo::{OutPoint, TxOut, UtxoDiff, UtxoSet};
+
+use super::blockchain_db::BlockChainDB;
+
+/// 쓰지 않은 출력을 담는 RocksDB 컬럼 패밀리 (키 `txid:vout`, 값 JSON `[OutPoint, TxOut]`)
+pub(super) const UTXO_CF: &str = "utxo";
+
+/// UTXO 집합에 마지막으로 반영한 블록 높이를 담는 키 (기본 컬럼 패밀리)
+pub(super) const UTXO_HEIGHT_KEY: &str = "utxo_height";
+
+impl BlockChainDB {
+    /// 저장된 `chain_id` 체인의 UTXO 집합 (체인 끝까지 반영되어 있지 않으면 체인을 다시 재생)
+    /// 체인 아이디가 바뀌면 `save_chain_id`가 저장된 출력을 지우므로 여기서는 높이만 확인
+    pub fn load_utxos(&self, chain_id: u32) -> UtxoSet {
+        let height = self.raw_get(UTXO_HEIGHT_KEY).and_then(|value| bincode::deserialize::<u64>(&value).ok());
+        if height.is_none() || height != self.load_latest_index() {
+            return self.rebuild_utxos(chain_id);
+        }
+        let outputs = self
+            .db()
+            .iterator_cf(self.utxo_cf(), rocksdb::IteratorMode::Start)
+            .filter_map(|item| item.ok())
+            .filter_map(|(_key, value)| serde_json::from_slice::<(OutPoint, TxOut)>(&value).ok());
+        UtxoSet::from_outputs(height.unwrap_or(0), chain_id, outputs)
+    }
+
+    /// 제네시스부터 체인을 재생해 UTXO 집합을 다시 만들고 컬럼 패밀리를 통째로 바꿈
+    /// 규칙을 어긴 블록이 있으면 그 앞 블록까지만 반영
+    pub fn rebuild_utxos(&self, chain_id: u32) -> UtxoSet {
+        let mut set = UtxoSet::new(chain_id);
+        for block in self.load_all_blocks().iter().filter(|block| block.index > 0) {
+            if let Err(e) = set.apply_block(block) {
+                eprintln!("Stopped replaying unspent outputs at block {}: {}", block.index, e);
+                break;
+            }
+        }
+
+        let mut batch = WriteBatch::default();
+        self.clear_utxos(&mut batch);
+        let cf = self.utxo_cf();
+        for (outpoint, output) in set.iter() {
+            let value = serde_json::to_vec(&(outpoint, output)).expect("출력 직렬화 실패");
+            batch.put_cf(cf, outpoint.to_string(), value);
+        }
+        batch.put(UTXO_HEIGHT_KEY, bincode::serialize(&set.height).expect("높이 직렬화 실패"));
+        self.db().write(batch).expect("UTXO 집합 저장 실패");
+        set
+    }
+
+    /// 블록 하나를 반영하며 바뀐 출력만 저장
+    pub fn save_utxo_diff(&self, diff: &UtxoDiff, height: u64) {
+        let mut batch = WriteBatch::default();
+        self.stage_utxo_diff(&mut batch, diff);
+        batch.put(UTXO_HEIGHT_KEY, bincode::serialize(&height).expect("높이 직렬화 실패"));
+        self.db().write(batch).expect("UTXO 집합 저장 실패");
+    }
+
+    /// 저장된 출력을 모두 지우도록 `batch`에 넣음 (`reset_db`, 다시 재생할 때)
+    pub(super) fn clear_utxos(&self, batch: &mut WriteBatch) {
+        let cf = self.utxo_cf();
+        for (key, _value) in self.db().iterator_cf(cf, rocksdb::IteratorMode::Start).filter_map(|item| item.ok()) {
+            batch.delete_cf(cf, key);
+        }
+        batch.delete(UTXO_HEIGHT_KEY);
+    }
+
+    /// 새 출력을 먼저 넣고 쓴 출력을 지움 (같은 블록에서 생겼다 쓰인 출력은 `diff`에 없음)
+    fn stage_utxo_diff(&self, batch: &mut WriteBatch, diff: &UtxoDiff) {
+        let cf = self.utxo_cf();
+        for (outpoint, output) in &diff.created {
+            let value = serde_json::to_vec(&(outpoint, output)).expect("출력 직렬화 실패");
+            batch.put_cf(cf, outpoint.to_string(), value);
+        }
+        for outpoint in &diff.spent {
+            batch.delete_cf(cf, outpoint.to_string());
+        }
+    }
+
+    fn utxo_cf(&self) -> &ColumnFamily {
+        self.db().cf_handle(UTXO_CF).expect("utxo 컬럼 패밀리가 없음")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::tests::temp_db;
+    use crate::blockchain::blockchain_db::{Block, BlockBody, Transaction};
+    use blockchain_common::body::BLOCK_REWARD;
+    use blockchain_common::test_support::block;
+
+    fn reward(height: u64, miner: &str) -> OutPoint {
+        OutPoint::coinbase(height, &BlockBody::reward(miner, BLOCK_REWARD).coinbase)
+    }
+
+    /// alice 가 1번 블록 보상을 bob 에게 일부 보내는 체인
+    fn chain() -> Vec<Block> {
+        let payment = Transaction { inputs: vec![reward(1, "alice")], ..Transaction::new("alice", "bob", 4) };
+        vec![block(1, "alice", vec![]), block(2, "bob", vec![payment])]
+    }
+
+    fn save_chain(db: &BlockChainDB, blocks: &[Block]) {
+        for block in blocks {
+            db.save_block(block);
+            db.save_latest_index(block.index);
+        }
+    }
+
+    #[test]
+    fn saved_diffs_match_a_full_replay() {
+        let db = temp_db("utxo-diffs");
+        db.reset_db();
+        let chain_id = db.load_chain_id();
+        let mut set = UtxoSet::new(chain_id);
+        for block in chain() {
+            save_chain(&db, std::slice::from_ref(&block));
+            let diff = set.apply_block(&block).unwrap();
+            db.save_utxo_diff(&diff, block.index);
+        }
+
+        let loaded = db.load_utxos(chain_id);
+        assert_eq!(loaded, set);
+        assert_eq!(loaded.get(&reward(1, "alice")), None);
+        assert_eq!(loaded.balance("alice"), BLOCK_REWARD - 4);
+        assert_eq!(loaded.balance("bob"), BLOCK_REWARD + 4);
+        assert_eq!(db.rebuild_utxos(chain_id), set);
+    }
+
+    #[test]
+    fn stale_sets_are_replayed_to_the_tip() {
+        let db = temp_db("utxo-stale");
+        db.reset_db();
+        let chain_id = db.load_chain_id();
+        let blocks = chain();
+        save_chain(&db, &blocks[..1]);
+        assert_eq!(db.rebuild_utxos(chain_id).height, 1);
+
+        // 블록만 저장하고 출력은 반영하지 않음
+        save_chain(&db, &blocks[1..]);
+        let loaded = db.load_utxos(chain_id);
+        assert_eq!(loaded.height, 2);
+        assert_eq!(loaded, UtxoSet::replay(chain_id, &blocks).unwrap());
+    }
+
+    #[test]
+    fn rebuilds_stop_before_a_broken_block() {
+        let db = temp_db("utxo-broken");
+        db.reset_db();
+        let chain_id = db.load_chain_id();
+        let double_spend = Transaction { inputs: vec![reward(1, "alice")], ..Transaction::new("alice", "carol", 1) };
+        let mut blocks = chain();
+        blocks.push(block(3, "carol", vec![double_spend]));
+        save_chain(&db, &blocks);
+
+        let set = db.rebuild_utxos(chain_id);
+        assert_eq!(set.height, 2);
+        assert_eq!(set.balance("carol"), 0);
+        // 반영한 높이가 체인 끝과 다르므로 다음에도 다시 재생함
+        assert_eq!(db.load_utxos(chain_id), set);
+    }
+
+    #[test]
+    fn reset_clears_stored_outputs() {
+        let db = temp_db("utxo-reset");
+        db.reset_db();
+        let chain_id = db.load_chain_id();
+        save_chain(&db, &chain());
+        assert!(!db.rebuild_utxos(chain_id).is_empty());
+
+        db.reset_db();
+        let loaded = db.load_utxos(chain_id);
+        assert!(loaded.is_empty());
+        assert_eq!(loaded.height, 0);
+    }
+}
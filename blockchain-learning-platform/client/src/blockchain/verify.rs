@@ -0,0 +1,421 @@
+// client/src/blockchain/verify.rs
+
+use thiserror::Error;
+
+use blockchain_common::chain::{self, genesis_hash, BlockError};
+use blockchain_common::ledger::Ledger;
+use blockchain_common::identity;
+use blockchain_common::pow;
+use blockchain_common::puzzles;
+
+use super::blockchain_db::{Block, BlockChainDB, Round};
+use super::ledger::BlockRules;
+
+/// 체인에서 블록 하나가 어긴 규칙
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChainError {
+    #[error("latest block index is missing")]
+    NoLatestIndex,
+    #[error("block is missing from the database")]
+    Missing,
+    #[error("block could not be decoded: {0}")]
+    Corrupt(String),
+    #[error("genesis block differs from the fixed genesis block")]
+    BadGenesis,
+    #[error(transparent)]
+    Rule(#[from] BlockError),
+}
+
+/// 처음으로 규칙을 어긴 블록
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenBlock {
+    pub index: u64,
+    pub error: ChainError,
+}
+
+/// `verify_chain` 결과: 제네시스부터 몇 개의 블록을 확인했고, 어디서 끊겼는지
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainReport {
+    pub latest_index: Option<u64>,
+    pub verified: u64, // 규칙을 모두 통과한 블록 수 (제네시스 포함)
+    pub broken: Option<BrokenBlock>,
+}
+
+impl ChainReport {
+    /// 화면에 표시할 한 줄 요약
+    pub fn summary(&self) -> String {
+        match &self.broken {
+            None => format!("Chain OK: {} block(s) verified", self.verified),
+            Some(broken) => format!(
+                "Chain broken at block {}: {} ({} block(s) verified before it)",
+                broken.index, broken.error, self.verified
+            ),
+        }
+    }
+}
+
+impl BlockChainDB {
+    /// 제네시스부터 `latest_block_index`까지 걸어가며 체인을 검사
+    ///
+    /// 높이 연속성, 해시 연결, 머클 루트, 블록과 거래의 서명, 발행 규칙대로의 보상, 블록 용량, 시각 순서, 체인이 정한 문제인지, 풀이(또는 작업 증명)와
+    /// 거래를 이 체인의 원장에 반영할 수 있는지(잔액, 이중 지불) 확인하고, 처음으로 규칙을 어긴 블록에서 멈춘다.
+    pub fn verify_chain(&self) -> ChainReport {
+        let mut report = ChainReport { latest_index: self.load_latest_index(), verified: 0, broken: None };
+        let Some(latest_index) = report.latest_index else {
+            report.broken = Some(BrokenBlock { index: 0, error: ChainError::NoLatestIndex });
+            return report;
+        };
+
+        let mut parent: Option<Block> = None;
+        let mut ledger = Ledger::new(self.load_ledger_model(), self.load_chain_id());
+        let history = self.load_rule_history();
+        for index in 0..=latest_index {
+            let checked = self
+                .read_block(index)
+                .map_err(|e| ChainError::Corrupt(e.to_string()))
+                .and_then(|block| block.ok_or(ChainError::Missing))
+                .and_then(|block| {
+                    match &parent {
+                        None => check_genesis(&block)?,
+                        Some(parent) => {
+                            check_link(parent, &block, &history.at(block.index))?;
+                            ledger.apply_block(&block)?;
+                        }
+                    }
+                    Ok(block)
+                });
+            match checked {
+                Ok(block) => {
+                    report.verified += 1;
+                    parent = Some(block);
+                }
+                Err(error) => {
+                    report.broken = Some(BrokenBlock { index, error });
+                    break;
+                }
+            }
+        }
+        report
+    }
+}
+
+/// 제네시스 블록은 모든 노드가 같은 고정된 블록
+fn check_genesis(block: &Block) -> Result<(), ChainError> {
+    if block.index != 0 || block.compute_hash() != genesis_hash() || block.check_merkle_root().is_err() {
+        return Err(ChainError::BadGenesis);
+    }
+    Ok(())
+}
+
+/// 블록이 겨냥한 라운드(`round_id`)에서 서버가 낸 문제를 그대로, 그 라운드의 난이도로 담았는지
+///
+/// 합의되자마자 서버가 다음 라운드를 (난이도도 다시 정해) 내므로, 늦게 검증하는 노드에게 지금 라운드는 블록의 라운드가 아닐 수 있다.
+/// 그래서 아는 라운드(`known`) 가운데 아이디가 같은 것과 비교하고, 없으면 건너뛴다
+/// (그때도 `check_link`가 문제 규칙으로 문제를 다시 만들어 봄).
+pub(crate) fn check_round(block: &Block, known: &[&Round]) -> Result<(), BlockError> {
+    let Some(round) = known.iter().find(|round| round.id == block.round_id) else {
+        return Ok(());
+    };
+    if block.problem != round.problem {
+        return Err(BlockError::ProblemNotIssued { height: round.height });
+    }
+    chain::check_difficulty(block.difficulty, &block.problem, round.problem.board().difficulty)
+}
+
+/// `block`이 `parent` 바로 다음 블록으로서 규칙을 지키는지
+///
+/// 보상, 블록 용량, 문제 규칙은 그 블록의 높이에 적용되던 규칙(`rules`)으로 검사한다.
+/// 문제의 종류, 크기, 작업 증명 목표는 블록이 적어 온 값이 아니라 문제 규칙으로 다시 정한다.
+/// 난이도는 서버가 풀이 시간으로 정하므로 저장된 체인만으로는 다시 계산할 수 없고,
+/// 헤더와 문제 판의 난이도가 서로 맞는지만 본다 (라운드 난이도와의 비교는 블록을 받을 때 함).
+pub(crate) fn check_link(parent: &Block, block: &Block, rules: &BlockRules) -> Result<(), ChainError> {
+    let tip = parent.as_tip();
+    if block.index != tip.next_height() {
+        return Err(BlockError::WrongHeight { expected: tip.next_height(), actual: block.index }.into());
+    }
+
+    let tip_hash = tip.hash_hex();
+    if block.prev_hash != tip_hash {
+        return Err(BlockError::PrevHashMismatch { expected: tip_hash, actual: block.prev_hash.clone() }.into());
+    }
+    block.check_merkle_root()?;
+    let hash = hex::encode(block.compute_hash());
+    if block.hash != hash {
+        return Err(BlockError::HashMismatch { expected: hash, actual: block.hash.clone() }.into());
+    }
+
+    identity::verify_block(&block.header(), &block.signature).map_err(BlockError::from)?;
+    identity::verify_transactions(&block.body)?;
+    rules.emission.check_coinbase(block)?;
+    chain::check_capacity(&block.body, rules.max_transactions)?;
+
+    // 저장된 체인은 지금 시각 기준으로 검사 (부모보다 이르거나 미래의 블록은 거절)
+    chain::check_timestamp(block.timestamp, parent.timestamp, chain::now_millis(), chain::DEFAULT_MAX_CLOCK_SKEW_MS)?;
+
+    rules.issue.check_problem(&block.problem, &tip)?;
+    chain::check_difficulty(block.difficulty, &block.problem, block.problem.board().difficulty)?;
+    match rules.issue.work_target {
+        Some(target_bits) => pow::verify_work(&block.header(), target_bits).map_err(BlockError::from)?,
+        None => puzzles::verify(&block.problem, &block.solution).map_err(BlockError::from)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_db::tests::temp_db;
+    use crate::blockchain::blockchain_db::{BlockBody, Transaction};
+    use crate::blockchain::ledger::RuleHistory;
+    use crate::blockchain::pow::mine;
+    use blockchain_common::chain::{ChainTip, IssueRules};
+    use blockchain_common::emission::Emission;
+    use blockchain_common::identity::SigningKey;
+
+    const TARGET_BITS: u32 = 4;
+
+    fn rules() -> BlockRules {
+        BlockRules { issue: IssueRules { work_target: Some(TARGET_BITS), ..IssueRules::default() }, ..BlockRules::default() }
+    }
+
+    /// `parent` 다음에 `rules`대로 올 작업 증명 블록 (내 키로 서명)
+    fn next_block(parent: &Block, key: &SigningKey, rules: &BlockRules) -> Block {
+        mined_block(parent, key, rules, vec![])
+    }
+
+    /// `parent` 다음에 `rules`대로 `transactions`를 담아 올 작업 증명 블록 (내 키로 서명)
+    fn mined_block(parent: &Block, key: &SigningKey, rules: &BlockRules, transactions: Vec<Transaction>) -> Block {
+        let me = identity::node_id(key);
+        let tip = parent.as_tip();
+        let mut block = Block::new(
+            tip.next_height(),
+            tip.hash_hex(),
+            parent.timestamp + 1_000,
+            rules.issue.issue(&tip, 0),
+            vec![],
+            vec![],
+            me.clone(),
+            BlockBody::with_fees(me, rules.emission.reward(tip.next_height()), transactions),
+        );
+        block.difficulty_target = TARGET_BITS;
+        block.nonce = mine(block.header()).0;
+        block.seal();
+        block.signature = identity::sign_block(key, &block.header());
+        block
+    }
+
+    /// `key`가 bob 에게 1씩 보내는 서명된 거래 (nonce 마다 한 건)
+    fn payments(key: &SigningKey, nonces: std::ops::Range<u64>) -> Vec<Transaction> {
+        let me = identity::node_id(key);
+        nonces
+            .map(|nonce| {
+                let mut payment = Transaction { nonce, ..Transaction::new(me.clone(), "bob", 1) };
+                payment.signature = identity::sign_transaction(key, &payment);
+                payment
+            })
+            .collect()
+    }
+
+    /// 제네시스 위에 블록 `length`개를 이은 DB
+    fn chain_db(name: &str, length: u64) -> (BlockChainDB, Vec<Block>) {
+        let db = temp_db(name);
+        db.reset_db();
+        db.save_block_rules(1, rules());
+        let mut blocks = vec![Block::genesis()];
+        extend(&db, &mut blocks, length, &rules());
+        (db, blocks)
+    }
+
+    /// 체인 끝에 `rules`대로 블록 `length`개를 더 이어 저장
+    fn extend(db: &BlockChainDB, blocks: &mut Vec<Block>, length: u64, rules: &BlockRules) {
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        for _ in 0..length {
+            let block = next_block(blocks.last().unwrap(), &key, rules);
+            db.save_block(&block);
+            db.save_latest_index(block.index);
+            blocks.push(block);
+        }
+    }
+
+    fn round(id: u64, height: u64, difficulty: u32) -> Round {
+        let tip = ChainTip { height: height - 1, hash: [id as u8; 32] };
+        Round {
+            id,
+            height,
+            problem: IssueRules::default().issue(&tip, difficulty),
+            transactions: vec![],
+            ledger: Default::default(),
+            chain_id: chain::default_chain_id(),
+            emission: Emission::default(),
+            rules: IssueRules::default(),
+            max_transactions: chain::default_max_block_transactions(),
+        }
+    }
+
+    /// `round`의 문제를 담은 블록
+    fn proposed(round: &Round) -> Block {
+        let mut block = Block::new(
+            round.height,
+            String::new(),
+            chain::GENESIS_TIMESTAMP,
+            round.problem.clone(),
+            vec![],
+            vec![],
+            "node".to_string(),
+            BlockBody::reward("node", 10),
+        );
+        block.round_id = round.id;
+        block
+    }
+
+    #[test]
+    fn committed_blocks_are_checked_against_their_own_round() {
+        // 다음 라운드는 난이도도 다시 정해짐
+        let (proposed_round, next_round) = (round(3, 1, 5), round(4, 2, 7));
+        let block = proposed(&proposed_round);
+        // 합의 뒤 다음 라운드가 와도 블록을 받을 때의 라운드로 확인
+        assert_eq!(check_round(&block, &[&proposed_round, &next_round]), Ok(()));
+        // 블록의 라운드를 모르면 건너뜀 (문제 규칙은 `check_link`가 확인)
+        assert_eq!(check_round(&block, &[&next_round]), Ok(()));
+
+        let mut other = proposed(&next_round);
+        other.round_id = proposed_round.id;
+        assert_eq!(
+            check_round(&other, &[&proposed_round, &next_round]),
+            Err(BlockError::ProblemNotIssued { height: 1 })
+        );
+
+        let mut easier = block.clone();
+        easier.difficulty = 4;
+        assert_eq!(
+            check_round(&easier, &[&proposed_round, &next_round]),
+            Err(BlockError::WrongDifficulty { expected: 5, actual: 4 })
+        );
+    }
+
+    #[test]
+    fn intact_chains_verify() {
+        let (db, _) = chain_db("verify-intact", 3);
+        let report = db.verify_chain();
+        assert_eq!(report.broken, None);
+        assert_eq!(report.verified, 4);
+    }
+
+    #[test]
+    fn blocks_are_checked_against_the_rules_at_their_height() {
+        let (db, mut blocks) = chain_db("verify-rule-change", 2);
+        // 높이 3 부터 보상과 블록 용량이 바뀜 (앞선 블록은 예전 규칙으로 만들어졌음)
+        let changed = BlockRules { emission: Emission::Fixed { reward: 25 }, max_transactions: 1, ..rules() };
+        db.save_block_rules(3, changed.clone());
+        extend(&db, &mut blocks, 2, &changed);
+        let report = db.verify_chain();
+        assert_eq!(report.broken, None);
+        assert_eq!(report.verified, 5);
+
+        // 바뀐 뒤의 블록이 예전 보상을 받으면 거기서 끊김
+        db.save_latest_index(2);
+        blocks.truncate(3);
+        extend(&db, &mut blocks, 1, &rules());
+        let broken = db.verify_chain().broken.unwrap();
+        assert_eq!(broken.index, 3);
+        assert!(matches!(broken.error, ChainError::Rule(BlockError::WrongReward { .. })));
+    }
+
+    #[test]
+    fn lowering_the_capacity_keeps_earlier_full_blocks() {
+        let db = temp_db("verify-capacity-change");
+        db.reset_db();
+        db.save_block_rules(1, rules());
+        let key = identity::generate_key(&mut rand::rngs::OsRng);
+        let mut blocks = vec![Block::genesis()];
+        blocks.push(mined_block(&blocks[0], &key, &rules(), vec![]));
+        blocks.push(mined_block(&blocks[1], &key, &rules(), payments(&key, 0..2)));
+        for block in &blocks[1..] {
+            db.save_block(block);
+            db.save_latest_index(block.index);
+        }
+        // 높이 3 부터 거래 한 건까지만
+        let smaller = BlockRules { max_transactions: 1, ..rules() };
+        db.save_block_rules(3, smaller.clone());
+        assert_eq!(db.verify_chain().broken, None);
+
+        let full = mined_block(&blocks[2], &key, &smaller, payments(&key, 2..4));
+        db.save_block(&full);
+        db.save_latest_index(full.index);
+        let broken = db.verify_chain().broken.unwrap();
+        assert_eq!(broken.index, 3);
+        assert_eq!(broken.error, ChainError::Rule(BlockError::TooManyTransactions { max: 1, actual: 2 }));
+    }
+
+    #[test]
+    fn rule_history_replaces_later_activations() {
+        let mut history = RuleHistory::default();
+        assert_eq!(history.at(5), BlockRules::default());
+        assert!(history.activate(1, rules()));
+        assert!(!history.activate(4, rules()));
+        let changed = BlockRules { max_transactions: 1, ..rules() };
+        assert!(history.activate(4, changed.clone()));
+        assert_eq!(history.at(0), rules());
+        assert_eq!(history.at(3), rules());
+        assert_eq!(history.at(4), changed);
+        assert_eq!(history.latest(), changed);
+        // 낮은 높이에서 다시 알려 주면 그 뒤의 기록은 버림
+        assert!(history.activate(2, rules()));
+        assert_eq!(history.at(9), rules());
+    }
+
+    #[test]
+    fn tampered_bodies_break_the_chain_there() {
+        let (db, mut blocks) = chain_db("verify-tampered", 3);
+        blocks[2].body.coinbase.amount += 1;
+        db.save_block(&blocks[2]);
+        let report = db.verify_chain();
+        assert_eq!(report.verified, 2);
+        let broken = report.broken.unwrap();
+        assert_eq!(broken.index, 2);
+        assert!(matches!(broken.error, ChainError::Rule(BlockError::MerkleRootMismatch { .. })));
+    }
+
+    #[test]
+    fn unlinked_blocks_break_the_chain_there() {
+        let (db, mut blocks) = chain_db("verify-unlinked", 2);
+        blocks[2].prev_hash = "00".repeat(32);
+        blocks[2].seal();
+        db.save_block(&blocks[2]);
+        let broken = db.verify_chain().broken.unwrap();
+        assert_eq!(broken.index, 2);
+        assert!(matches!(broken.error, ChainError::Rule(BlockError::PrevHashMismatch { .. })));
+    }
+
+    #[test]
+    fn blocks_signed_by_another_key_break_the_chain() {
+        let (db, mut blocks) = chain_db("verify-signature", 2);
+        let other = identity::generate_key(&mut rand::rngs::OsRng);
+        blocks[1].signature = identity::sign_block(&other, &blocks[1].header());
+        db.save_block(&blocks[1]);
+        let broken = db.verify_chain().broken.unwrap();
+        assert_eq!(broken.index, 1);
+        assert!(matches!(broken.error, ChainError::Rule(BlockError::Signature(_))));
+    }
+
+    #[test]
+    fn missing_and_corrupt_blocks_are_reported() {
+        let (db, _) = chain_db("verify-missing", 1);
+        db.save_latest_index(2);
+        let report = db.verify_chain();
+        assert_eq!(report.broken, Some(BrokenBlock { index: 2, error: ChainError::Missing }));
+        assert_eq!(report.verified, 2);
+
+        db.raw_put("block_00000001", b"not a block");
+        let broken = db.verify_chain().broken.unwrap();
+        assert_eq!(broken.index, 1);
+        assert!(matches!(broken.error, ChainError::Corrupt(_)));
+    }
+
+    #[test]
+    fn databases_without_a_latest_index_are_broken_at_genesis() {
+        let report = temp_db("verify-empty").verify_chain();
+        assert_eq!(report.broken, Some(BrokenBlock { index: 0, error: ChainError::NoLatestIndex }));
+        assert_eq!(report.verified, 0);
+    }
+}
@@ -5,13 +5,22 @@ mod views;
 mod blockchain;
 mod network;
 
-use blockchain::blockchain_db::Problem;
+use blockchain::blockchain_db::{BlockBody, Board, Coinbase, Problem, Round, Transaction};
+use blockchain_common::ledger::{Ledger, LedgerModel};
+use blockchain_common::protocol::{OutPoint, TransactionError};
+use blockchain_common::chain::{self, ChainTip};
+use blockchain_common::identity::{self, SigningKey};
 use tokio::sync::mpsc::unbounded_channel;
 use views::problem_solving::view_problem_solving;
 use views::chain_info::view_chain_info;
+use views::wallet::view_wallet;
 use views::block_verification::view_block_verification;
 
 use blockchain::blockchain_db::{Block, BlockChainDB};
+use blockchain::ledger::BlockRules;
+use blockchain::verify::{self, ChainReport};
+use blockchain::fees::estimate_fee;
+use views::timezone::UtcOffset;
 
 // ------------------------------
 // iced 관련 import 정리
@@ -29,12 +38,12 @@ use iced::{
 
 use iced_aw::{TabLabel, Tabs};
 use rand::{Rng, thread_rng};
-use chrono::{DateTime, TimeZone, Utc, FixedOffset};
 
 // 추가: network 모듈 관련 임포트
 use crate::network::ServerMessage as netServerMessage;
 use crate::network::ValidationResult;
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use iced::subscription::unfold;
@@ -46,8 +55,11 @@ use iced::{Subscription, Event, event::Status};
 enum Message {
     TabSelected(usize),
     SubmitSolution,
+    StartMining,                        // 작업 증명 모드: nonce 찾기 시작
     InputChanged(usize, usize, String), // (행, 열, 새로운 값)
     LoadChainInfo,                      // 체인 정보를 로드하는 메시지 ***
+    VerifyChain,                        // 로컬 체인 전체 검증
+    TimezoneSelected(UtcOffset),        // 블록 시각을 보여줄 시간대 선택
     ResetDB,          // DB 초기화 메시지
     AddRandomBlock,   // 블록 추가 메시지
 
@@ -61,8 +73,13 @@ enum Message {
 
     ReceivedProposedBlock(Option<netServerMessage>),
     // 거래 관련 메시지
-    TransactionSubmit(String, String, u32), // (sender, receiver, amount)// ***
+    TransactionSubmit(String, String, u64, u64), // (sender, receiver, amount, fee)// ***
     TransactionFinished(Result<(), String>),// ***
+    // 지갑: 보내기 양식 입력과 보내기 버튼
+    RecipientChanged(String),
+    AmountChanged(String),
+    FeeChanged(String),      // 비워 두면 추천 수수료
+    SendCoins,
 
     NoMoreMessages,
     
@@ -71,19 +88,35 @@ enum Message {
 // 메인 상태 구조체
 struct BlockchainClientGUI {
     active_tab: usize,
-    solution_input: [[String; 4]; 4], // 4x4 정답 입력 상태
-    transaction_input: (String, String, String), // (sender, receiver, amount)
+    solution_input: Vec<Vec<String>>, // n x n 정답 입력 상태 (문제 크기에 맞춰 조정)
+    transaction_input: (String, String, String), // 지갑 보내기 양식 (receiver, amount, fee)
     blocks: Vec<Block>,               // 로드된 블록 리스트
     db: BlockChainDB,                 // DB 인스턴스
     // 추가: 서버 메시지를 수신하기 위한 채널
     server_msg_receiver: Option<Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<netServerMessage>>>>,
     // /// (가정) 서버에서 받은 블록(하드코딩)
     proposed_block: Option<(Block, bool)>,
-    // 서버에서 받은 현재 문제
-    current_problem: Option<Problem>,
-    // 내 정보
+    // 블록을 받을 때의 라운드 (블록이 겨냥한 라운드일 때만, 검증 전에 다음 라운드가 와도 그 라운드로 확인)
+    proposed_round: Option<Round>,
+    // 서버에서 받은 현재 라운드 (문제, 라운드 번호, 높이)
+    current_round: Option<Round>,
+    // 작업 증명 모드에서 nonce 를 찾는 중인지
+    mining: bool,
+    // 마지막 "Verify Chain" 결과
+    chain_report: Option<ChainReport>,
+    // 서버 멤풀에 대기 중인 거래 (WebSocket 으로 받음)
+    mempool: Vec<Transaction>,
+    // 블록 시각을 보여줄 시간대 (블록에는 UTC 로 저장)
+    timezone: UtcOffset,
+    // 내 정보: 블록에 서명하는 키와 그 공개 키로 만든 아이디
+    signing_key: SigningKey,
     my_node_id: String,
-    my_balance: u64, 
+    // 합의된 블록을 재생한 원장 (내 잔액은 `ledger.balance(&my_node_id)`)
+    ledger: Ledger,
+    // 지금 라운드의 합의 규칙: 보상, 문제 규칙, 블록 용량 (서버가 라운드에 담아 알려 줌, 높이마다 기록)
+    block_rules: BlockRules,
+    // 지갑에서 마지막으로 보낸 거래의 결과
+    wallet_status: Option<String>,
 }
 
 impl BlockchainClientGUI {
@@ -97,12 +130,19 @@ impl BlockchainClientGUI {
             db.reset_db();  // reset_db 내부에서 제네시스 블록 생성
         }
 
+        // 내 정보
+        // 노드 아이디는 저장된 키의 공개 키 (재시작해도 같은 아이디)
+        let signing_key = db.load_or_create_key();
+        let my_node_id = identity::node_id(&signing_key);
+
+        // 예전 형식(`data` 문자열, nonce 없는 거래)으로 저장된 블록을 지금 형식으로 변환
+        db.migrate(&signing_key);
+
         // 시작 시 DB에서 기존 블록들을 불러옵니다.
         let blocks = db.load_all_blocks();
-
-        // 내 정보
-        let my_node_id = Self::generate_random_node_id();
-        let my_balance = 0;
+        // 저장된 원장이 체인 끝과 맞지 않으면 체인을 다시 재생
+        let ledger = db.load_ledger();
+        let block_rules = db.load_rule_history().latest();
 
         // 2) 채널 생성
         let (tx, rx) = unbounded_channel::<netServerMessage>();
@@ -113,94 +153,206 @@ impl BlockchainClientGUI {
         // 4) 구조체 생성
         let gui = BlockchainClientGUI {
             active_tab: 0,
-            solution_input: Default::default(),
+            solution_input: vec![vec![String::new(); 4]; 4],
             transaction_input: (String::new(), String::new(), String::new()),
             blocks,
             db,
             // 바뀐 부분
             server_msg_receiver: Some(rx_arc),
             proposed_block: None,
-            current_problem: None, // 현재 문제 초기화
+            proposed_round: None,
+            current_round: None, // 현재 라운드 초기화
+            mining: false,
+            chain_report: None,
+            mempool: vec![],
+            timezone: UtcOffset::default(),
+            signing_key,
             my_node_id,
-            my_balance,
+            ledger,
+            block_rules,
+            wallet_status: None,
         };
         (gui, tx)
     }
 
-    fn generate_random_node_id() -> String {
-        let mut rng = thread_rng();
-        let names = ["SangNamJa", "HeeSeungSim", "SeungJaeLee","MadCamp2024W","JunhoKim","JunhoPark"];
-        // 예: A, B, C 중 하나 선택
-        let chosen = names[rng.gen_range(0..names.len())];
-        // 1~999 범위 중 3자리로 포맷
-        let number = rng.gen_range(1..1000);
-        format!("{}{:03}", chosen, number)
-    }
-
     /// 임의의 블록 추가
     fn add_random_block(&mut self) {
         let mut rng = thread_rng();
-        let problem1 = Problem {
-            matrix: vec![
-                vec![1, 2, 3, 4],
-                vec![5, 6, 7, 8],
-                vec![9, 10, 11, 12],
-                vec![13, 14, 15, 16],
-            ],
-        };
-        let problem2 = Problem {
+        let problem2 = Problem::MagicSquare(Board {
+            size: 4,
             matrix: vec![
                 vec![1, 2, 3, 4],
                 vec![5, 6, 7, 8],
                 vec![9, 10, 11, 12],
                 vec![13, 14, 15, 16],
             ],
-        };
+            difficulty: 0,
+            solution_count: Some(1),
+        });
         
         let solution = vec![vec![3, 4]];
 
-        let node_id = format!("Node{}", rng.gen_range(1..1000));
-        let data = format!("Random Data {}", rng.gen::<u32>());
-
         let latest_index = self.db.load_latest_index().unwrap_or(0);
 
         let latest_block = match self.db.load_block(latest_index) {
             Some(block) => block,
             None => {
-                let genesis_block = Block::new(
-                    0,
-                    problem1,
-                    vec![],
-                    vec![],
-                    "GenesisNode".into(),
-                    "Genesis Block".into(),
-                );
+                let genesis_block = Block::genesis();
                 self.db.save_block(&genesis_block);
                 self.db.save_latest_index(0);
                 genesis_block
             }
         };
 
-        let new_block = Block::new(
+        // 블록을 만든 노드: 거래에 서명할 수 있도록 이번 블록에만 쓰는 키를 만듦
+        let miner_key = identity::generate_key(&mut rng);
+        let node_id = identity::node_id(&miner_key);
+        let reward = self.block_rules.emission.reward(latest_block.index + 1);
+        let chain_id = self.ledger.chain_id();
+        // 보상 한 건과, 블록을 만든 노드가 받은 보상 안에서 보내는 무작위 거래
+        let transactions = match self.ledger.model() {
+            // 계정 원장: 보상을 나눠 몇 건 (수수료는 다시 이 노드가 받음)
+            LedgerModel::Account => {
+                let mut remaining = reward;
+                let first_nonce = self.ledger.next_nonce(&node_id);
+                let mut transactions = Vec::new();
+                for nonce in (first_nonce..).take(rng.gen_range(0..4)) {
+                    if remaining == 0 {
+                        break;
+                    }
+                    let amount = rng.gen_range(1..=remaining);
+                    let fee = rng.gen_range(0..=(remaining - amount).min(2));
+                    remaining -= amount + fee;
+                    let receiver_id = format!("Node{}", rng.gen_range(1..1000));
+                    transactions.push(Transaction { fee, nonce, chain_id, ..Transaction::new(node_id.clone(), receiver_id, amount) });
+                }
+                transactions
+            }
+            // UTXO 원장: 이 블록의 보상 출력을 쓰는 거래 한 건 (남은 금액은 거스름돈 출력)
+            // 수수료를 내면 코인베이스 금액, 즉 쓰려는 출력이 바뀌므로 수수료 없이
+            LedgerModel::Utxo if reward > 0 => vec![Transaction {
+                chain_id,
+                inputs: vec![OutPoint::coinbase(
+                    latest_block.index + 1,
+                    &Coinbase { receiver_id: node_id.clone(), amount: reward },
+                )],
+                ..Transaction::new(node_id.clone(), format!("Node{}", rng.gen_range(1..1000)), rng.gen_range(1..=reward))
+            }],
+            // 보상이 더 없으면 쓸 출력도 없음
+            LedgerModel::Utxo => vec![],
+        };
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| Transaction { signature: identity::sign_transaction(&miner_key, &transaction), ..transaction })
+            .collect();
+        let body = BlockBody::with_fees(node_id.clone(), reward, transactions);
+
+        let mut new_block = Block::new(
             latest_block.index + 1,
+            latest_block.hash.clone(),
+            chain::now_millis(),
             problem2,
             solution,
             latest_block.solution.clone(),
             node_id,
-            data,
+            body,
         );
+        new_block.signature = identity::sign_block(&miner_key, &new_block.header());
 
-        self.db.save_block(&new_block);
-        self.db.save_latest_index(new_block.index);
+        if let Err(e) = self.db.append_block(&new_block, &mut self.ledger) {
+            eprintln!("Failed to add random block {}: {}", new_block.index, e);
+            return;
+        }
 
         // 갱신
         self.blocks = self.db.load_all_blocks();
     }
 
+    /// 로컬 체인의 끝 (비어 있으면 제네시스)
+    fn chain_tip(&self) -> ChainTip {
+        self.blocks.last().map_or_else(ChainTip::genesis, Block::as_tip)
+    }
+
+    /// `sender`가 다음에 보낼 거래의 nonce: 로컬 체인의 다음 nonce 와 멤풀에 대기 중인 내 거래 다음 순번 중 큰 값
+    fn next_nonce(&self, sender: &str) -> u64 {
+        self.mempool
+            .iter()
+            .filter(|pending| pending.sender_id == sender)
+            .map(|pending| pending.nonce + 1)
+            .fold(self.ledger.next_nonce(sender), u64::max)
+    }
+
+    /// 지갑에서 지금 보낼 수 있는 금액: 합의된 잔액에서 멤풀에 대기 중인 내 거래의 금액과 수수료를 뺀 값
+    fn available_balance(&self) -> u64 {
+        self.mempool
+            .iter()
+            .filter(|pending| pending.sender_id == self.my_node_id)
+            .fold(self.ledger.balance(&self.my_node_id), |balance, pending| balance.saturating_sub(pending.cost()))
+    }
+
+    /// 현재 라운드의 문제에 대해 서버로 보낼 블록 구성 (nonce 는 0, 작업 증명 모드면 채굴 후 채움)
+    /// 라운드를 받기 전이면 라운드 번호 0 으로 보내 서버가 거절함
+    fn block_for_server(&self, solution: Vec<Vec<u32>>) -> Block {
+        let round = self.current_round.clone().unwrap_or_else(|| Round {
+            id: 0,
+            height: self.chain_tip().next_height(),
+            problem: Problem::empty(4),
+            transactions: vec![],
+            ledger: self.ledger.model(),
+            chain_id: self.ledger.chain_id(),
+            emission: self.block_rules.emission,
+            rules: self.block_rules.issue.clone(),
+            max_transactions: self.block_rules.max_transactions,
+        });
+
+        let prev_solution = if let Some(last_block) = self.blocks.last() {
+            last_block.solution.clone()
+        } else {
+            // 로컬 체인에 아무 블록이 없으면 빈 Vec
+            vec![]
+        };
+
+        // 작업 증명 문제는 판의 난이도가 목표 비트 수
+        let difficulty_target = match &round.problem {
+            Problem::ProofOfWork(board) => board.difficulty,
+            _ => 0,
+        };
+
+        let mut block = Block::new(
+            round.height,
+            self.chain_tip().hash_hex(),
+            chain::now_millis(),
+            round.problem,
+            solution,
+            prev_solution,
+            self.my_node_id.clone(),
+            // 발행 규칙이 정한 이 높이의 보상과, 서버가 라운드에 담아 보낸 블록 템플릿 거래의 수수료
+            BlockBody::with_fees(self.my_node_id.clone(), round.emission.reward(round.height), round.transactions),
+        );
+        block.difficulty_target = difficulty_target;
+        block.round_id = round.id;
+        block.seal();
+        block.signature = identity::sign_block(&self.signing_key, &block.header());
+        block
+    }
+
+    /// 블록에 대한 내 투표 (내 키로 서명)
+    fn vote(&self, block: &Block, is_valid: bool) -> ValidationResult {
+        ValidationResult {
+            is_valid,
+            node_id: self.my_node_id.clone(),
+            round_id: block.round_id,
+            block_hash: block.hash.clone(),
+            signature: identity::sign_vote(&self.signing_key, block.round_id, &block.hash, is_valid),
+        }
+    }
+
     /// DB 초기화
     fn reset_db(&mut self) {
         self.db.reset_db();
         self.blocks = self.db.load_all_blocks();
+        self.ledger = self.db.load_ledger();
+        self.chain_report = None;
     }
 
 }
@@ -285,56 +437,20 @@ impl Application for BlockchainClientGUI {
             Message::SubmitSolution => {
                 println!("Solution submitted! Now sending to server...");
 
-                // 1) 4x4 string matrix -> Vec<Vec<u32>> 변환 (파싱)
+                // 1) n x n string matrix -> Vec<Vec<u32>> 변환 (파싱)
+                //    비었거나 숫자가 아닌 칸은 0으로 두어 모양을 유지 (서버가 어느 칸이 틀렸는지 알려줌)
                 let parsed_solution = self
                     .solution_input
                     .iter()
                     .map(|row| {
                         row.iter()
-                            .filter_map(|val| val.parse::<u32>().ok()) // 파싱 실패하면 버림
+                            .map(|val| val.trim().parse::<u32>().unwrap_or(0))
                             .collect::<Vec<u32>>()
                     })
                     .collect::<Vec<Vec<u32>>>();
 
-                let example_problem = self.current_problem.clone().unwrap_or_else(|| {
-                    Problem{matrix: vec![ vec![0;4]; 4 ]}
-                });
-
-                let prev_solution = if let Some(last_block) = self.blocks.last() {
-                    last_block.solution.clone()
-                } else {
-                    // 로컬 체인에 아무 블록이 없으면 빈 Vec
-                    vec![]
-                };
-
-                let my_node_id = self.my_node_id.clone();
-
-                let sys_timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("Time went backwards")
-                    .as_secs();
-                let datetime: DateTime<Utc> = Utc.timestamp_opt(sys_timestamp as i64, 0)
-                    .single()
-                    .expect("Invalid timestamp");
-
-                // 한국 시간으로 변환 (UTC+9)
-                let kst_offset = FixedOffset::east_opt(9 * 3600)
-                    .expect("Invalid offset");
-                let kst_datetime = datetime.with_timezone(&kst_offset);
-                
-                let timestamp= kst_datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-
-                // 2) 서버로 보낼 BlockForServer 구성 (임시 값들로 예시)
-                use network::BlockForServer;
-                let block_data = BlockForServer {
-                    index: 0, // 실제 블록 인덱스로 교체
-                    timestamp, 
-                    problem: example_problem,
-                    solution: parsed_solution,    
-                    prev_solution,           
-                    node_id: my_node_id,
-                    data: "10".to_string(),
-                };
+                // 2) 서버로 보낼 블록 구성
+                let block_data = self.block_for_server(parsed_solution);
 
                 // 3) 비동기 전송 - Command::perform 사용
                 //    http://143.248.196.38:3000 등 실제 서버 주소로 교체
@@ -349,8 +465,40 @@ impl Application for BlockchainClientGUI {
                 // Command::perform(...)를 반환하여 iced가 비동기 처리 후 메시지를 다시 보냄
                 Command::perform(future, Message::SubmitSolutionFinished)
             }
+            // 1)-a) 작업 증명 모드: 헤더 해시가 목표를 만족하는 nonce 를 찾아 제출
+            Message::StartMining => {
+                if self.mining {
+                    return Command::none();
+                }
+                self.mining = true;
+
+                let mut block_data = self.block_for_server(vec![]);
+                let signing_key = self.signing_key.clone();
+                println!("Mining for {} leading zero bits...", block_data.difficulty_target);
+
+                let server_url = "http://143.248.196.38:3000";
+                let future = async move {
+                    // 해시 계산은 CPU를 오래 쓰므로 블로킹 스레드에서 실행
+                    let template = block_data.clone();
+                    let (nonce, _hash) = tokio::task::spawn_blocking(move || {
+                        blockchain::pow::mine(template.header())
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    block_data.nonce = nonce;
+                    block_data.seal();
+                    println!("Found nonce {} (hash {})", nonce, block_data.hash);
+                    // nonce 가 바뀌었으니 다시 서명
+                    block_data.signature = identity::sign_block(&signing_key, &block_data.header());
+                    network::submit_solution_block(server_url, &block_data)
+                        .await
+                        .map_err(|e| e.to_string())
+                };
+                Command::perform(future, Message::SubmitSolutionFinished)
+            }
             // 1)-b) 블록 제출에 대한 결과 처리
             Message::SubmitSolutionFinished(result) => {
+                self.mining = false;
                 match result {
                     Ok(()) => println!("Server accepted the solution block successfully!"),
                     Err(err_msg) => eprintln!("Error submitting solution block: {}", err_msg),
@@ -361,8 +509,8 @@ impl Application for BlockchainClientGUI {
             // 2) '코인 채굴하기' 탭의 입력 필드
             Message::InputChanged(row, col, value) => {
                 // 블록 풀이 입력 필드
-                if row < 4 && col < 4 {
-                    self.solution_input[row][col] = value;
+                if let Some(cell) = self.solution_input.get_mut(row).and_then(|r| r.get_mut(col)) {
+                    *cell = value;
                 }
                 // 만약 거래 입력 필드를 여기서 처리한다면 추가 로직 필요 (지금은 미사용)
                 Command::none()
@@ -371,6 +519,21 @@ impl Application for BlockchainClientGUI {
             // 3) 로컬 체인 정보 로드
             Message::LoadChainInfo => {
                 self.blocks = self.db.load_all_blocks();
+                self.ledger = self.db.load_ledger();
+                Command::none()
+            }
+
+            // 3)-a) 로컬 체인 전체 검증
+            Message::VerifyChain => {
+                let report = self.db.verify_chain();
+                println!("{}", report.summary());
+                self.chain_report = Some(report);
+                Command::none()
+            }
+
+            // 3)-b) 블록 시각을 보여줄 시간대 변경
+            Message::TimezoneSelected(timezone) => {
+                self.timezone = timezone;
                 Command::none()
             }
 
@@ -389,75 +552,75 @@ impl Application for BlockchainClientGUI {
             }
             
             Message::VerifyBlock => {
-                        if let Some((proposed, _)) = self.proposed_block.take() {
-                            let latest_index = self.db.load_latest_index().unwrap_or(0);
-                    let latest_block = self.db.load_block(latest_index).unwrap_or_else(|| {
-                            // 로컬이 비어있다면 Genesis 블록 생성
-                            Block::new(
-                                0,
-                                Problem { matrix: vec![vec![0;4];4] },
-                                vec![],
-                                vec![],
-                                "GenesisNode".into(),
-                                "Genesis Block".into()
-                            )
-                        });
-                        
-                        // 새 블록 생성
-                        let new_block = Block::new(
-                            latest_block.index + 1,
-                            proposed.problem.clone(),
-                            proposed.solution.clone(),
-                            latest_block.solution.clone(), // 이전 블록의 solution
-                            proposed.node_id.clone(),
-                            proposed.data.clone()
-                        );
-
-                        // [CHANGED CODE] node_id가 자신의 아이디와 같으면 보상
-                    if proposed.node_id == self.my_node_id {
-                        self.my_balance += 10;
-                        println!(
-                            "블록 제출자가 나 자신이므로 보상으로 balance를 10 증가! 현재 잔액: {}",
-                            self.my_balance
-                        );
+                if let Some((proposed, _)) = self.proposed_block.take() {
+                    let latest_index = self.db.load_latest_index().unwrap_or(0);
+                    // 로컬이 비어있다면 Genesis 블록에서 시작
+                    let latest_block = self.db.load_block(latest_index).unwrap_or_else(Block::genesis);
+
+                    // 서버가 블록의 라운드에 낸 문제를 그대로, 그 라운드의 난이도로 담았는지 확인
+                    // (블록이 적어 온 종류, 크기, 난이도를 믿으면 같은 시드의 더 쉬운 문제도 통과함)
+                    // 합의되자마자 다음 라운드가 오므로 지금 라운드가 아니라 블록이 겨냥한 라운드와 비교
+                    let known: Vec<&Round> = self.proposed_round.iter().chain(&self.current_round).collect();
+                    if let Err(e) = verify::check_round(&proposed, &known) {
+                        println!("블록 검증 실패: 라운드 {} 의 문제나 난이도와 다름 ({})", proposed.round_id, e);
+                        self.proposed_block = Some((proposed, false));
+                        return self.update(Message::RejectBlock);
                     }
-                            
-                            // 검증 성공, 블록을 로컬 체인에 추가
-                            self.db.save_block(&new_block);
-                            self.db.save_latest_index(new_block.index);
-                            self.blocks = self.db.load_all_blocks();
-                            println!("로컬체인: {:?}", self.blocks.clone());
-                    
-                            // 서버로 검증 결과 전송
-                            let validation_result = ValidationResult {
-                                is_valid: true, // 검증 성공
-                                node_id: "client_node_id".to_string(),
-                            };
-                            let server_url = "http://143.248.196.38:3000";
-                            let future = async move {
-                                network::submit_validation_result(server_url, &validation_result)
-                                    .await
-                                    .map_err(|e| e.to_string())
-                            };
-                    
-                            println!("블록 검증 성공: 블록 추가 및 서버에 결과 전송");
-                            return Command::perform(future, Message::SubmitValidationFinished);
-                        } else {
-                            println!("검증할 블록이 없습니다!");
-                        }
-                        Command::none()
+
+                    // "Verify Chain" 과 같은 규칙으로 로컬 체인 끝 다음 블록인지 확인:
+                    // 높이와 해시 연결, 머클 루트, 블록과 거래의 서명, 보상, 블록 용량, 시각,
+                    // 문제 규칙으로 다시 만든 문제인지, 풀이(또는 작업 증명)까지 (보상을 주기 전에)
+                    // 규칙은 지금 라운드가 아니라 블록 높이에 적용되던 것으로
+                    let rules = self.db.load_rule_history().at(proposed.index);
+                    if let Err(e) = verify::check_link(&latest_block, &proposed, &rules) {
+                        println!("블록 검증 실패: {}", e);
+                        self.proposed_block = Some((proposed, false));
+                        return self.update(Message::RejectBlock);
                     }
-                    
+
+                    // 새 블록: 헤더(높이, 시각, nonce 등)는 그대로 두어야 서버와 같은 해시가 나옴
+                    let mut new_block = proposed.clone();
+                    new_block.prev_solution = latest_block.solution.clone(); // 이전 블록의 solution
+
+                    // 보상과 거래를 원장에 반영 (잔액보다 많이 보내거나 이미 쓴 출력을 다시 쓰는 거래가 있으면 거절)
+                    // 반영에 성공해야 블록과 원장을 함께 로컬 체인에 저장
+                    let balance_before = self.ledger.balance(&self.my_node_id);
+                    if let Err(e) = self.db.append_block(&new_block, &mut self.ledger) {
+                        println!("블록 검증 실패: 원장에 반영할 수 없음 ({})", e);
+                        self.proposed_block = Some((proposed, false));
+                        return self.update(Message::RejectBlock);
+                    }
+                    let balance_after = self.ledger.balance(&self.my_node_id);
+                    if balance_after != balance_before {
+                        println!("내 잔액: {} -> {}", balance_before, balance_after);
+                    }
+                    self.blocks = self.db.load_all_blocks();
+                    println!("로컬체인: {:?}", self.blocks.clone());
+
+                    // 서버로 검증 결과 전송
+                    let validation_result = self.vote(&new_block, true); // 검증 성공
+                    let server_url = "http://143.248.196.38:3000";
+                    let future = async move {
+                        network::submit_validation_result(server_url, &validation_result)
+                            .await
+                            .map_err(|e| e.to_string())
+                    };
+
+                    println!("블록 검증 성공: 블록 추가 및 서버에 결과 전송");
+                    return Command::perform(future, Message::SubmitValidationFinished);
+                } else {
+                    println!("검증할 블록이 없습니다!");
+                }
+                Command::none()
+            }
+
             Message::RejectBlock => {
                 if let Some((block, _)) = self.proposed_block.take() {
                     // 검증 실패, 블록 폐기
                     println!("블록 검증 실패: 블록 폐기 - {:?}", block);
             
                     // 서버로 검증 실패 결과 전송
-                    let validation_result = ValidationResult {
-                        is_valid: false, // 검증 실패
-                        node_id: "client_node_id".to_string(),
-                    };
+                    let validation_result = self.vote(&block, false); // 검증 실패
                     let server_url = "http://143.248.196.38:3000";
                     let future = async move {
                         network::submit_validation_result(server_url, &validation_result)
@@ -479,6 +642,8 @@ impl Application for BlockchainClientGUI {
             // 서버 메시지 처리: Block
             Message::ServerMessage(netServerMessage::Block(block)) => {
                 println!("서버에서 블록 수신: {:?}", block);
+                // 블록이 겨냥한 라운드를 같이 기억 (검증하기 전에 다음 라운드가 올 수 있음)
+                self.proposed_round = self.current_round.clone().filter(|round| round.id == block.round_id);
                 self.proposed_block = Some((block.clone(), false)); // 검증 대기 상태로 저장
                 Command::none()
             }
@@ -487,17 +652,38 @@ impl Application for BlockchainClientGUI {
         // ---------------------------------------------------------
         // 2) 거래 전송: 굳이 &self 메서드를 직접 async로 안 쓰는 방식
         // ---------------------------------------------------------
-            Message::TransactionSubmit(sender, receiver, amount) => {
-                // (1) 거래 데이터 (소유권) 생성
-                let transaction = network::Transaction {
-                    sender_id: sender,
-                    receiver_id: receiver,
-                    amount,
+            Message::TransactionSubmit(sender, receiver, amount, fee) => {
+                // (1) 거래 데이터 (소유권) 생성: 이 체인의 아이디와 로컬 체인에서 계산한 다음 nonce, 블록을 만든 노드에게 줄 수수료
+                let mut transaction = network::Transaction {
+                    fee,
+                    nonce: self.next_nonce(&sender),
+                    chain_id: self.ledger.chain_id(),
+                    ..network::Transaction::new(sender, receiver, amount)
                 };
-            // (2) 별도의 동기/검증 로직이 필요하다면, 여기서 self를 사용하고 즉시 끝냄
-            //     (예: self.db 잔액 조회)
+            // (2) UTXO 원장이면 보내는 쪽의 쓰지 않은 출력 중에서 금액과 수수료를 낼 입력을 고름
+            //     (멤풀에 대기 중인 거래가 이미 쓴 출력은 빼고)
+                if let Ledger::Utxo(utxos) = &self.ledger {
+                    let reserved: HashSet<OutPoint> =
+                        self.mempool.iter().flat_map(|pending| pending.inputs.iter().cloned()).collect();
+                    match utxos.select_coins(&transaction.sender_id, transaction.cost(), &reserved) {
+                        Ok(inputs) => transaction.inputs = inputs,
+                        Err(e) => {
+                            eprintln!("Cannot build transaction: {}", e);
+                            self.wallet_status = Some(format!("Cannot build transaction: {}", e));
+                            return Command::none();
+                        }
+                    }
+                }
 
-            // (3) 나머지 통신은 'static Future 로
+            // (3) 이 노드의 키로 서명 (키가 없는 다른 아이디로는 보낼 수 없음)
+                if transaction.sender_id != self.my_node_id {
+                    eprintln!("Cannot sign a transaction from {}: this node only holds the key of {}", transaction.sender_id, self.my_node_id);
+                    self.wallet_status = Some(format!("Cannot sign a transaction from {}", transaction.sender_id));
+                    return Command::none();
+                }
+                transaction.signature = identity::sign_transaction(&self.signing_key, &transaction);
+
+            // (4) 나머지 통신은 'static Future 로
                 let future = async move {
                     let server_url = "http://143.248.196.38:3000";
                     network::submit_transaction(server_url, &transaction)
@@ -507,6 +693,58 @@ impl Application for BlockchainClientGUI {
                 return Command::perform(future, Message::TransactionFinished);
             }
 
+            // 지갑 보내기 양식
+            Message::RecipientChanged(receiver) => {
+                self.transaction_input.0 = receiver;
+                Command::none()
+            }
+            Message::AmountChanged(amount) => {
+                self.transaction_input.1 = amount;
+                Command::none()
+            }
+            Message::FeeChanged(fee) => {
+                self.transaction_input.2 = fee;
+                Command::none()
+            }
+            Message::SendCoins => {
+                let (receiver, amount, fee) = &self.transaction_input;
+                let receiver = receiver.trim().to_string();
+                let Ok(amount) = amount.trim().parse::<u64>() else {
+                    self.wallet_status = Some(format!("Invalid amount: {:?}", amount));
+                    return Command::none();
+                };
+                // 수수료를 비워 두면 최근 블록의 수수료로 추천한 값
+                let fee = match fee.trim() {
+                    "" => estimate_fee(&self.blocks),
+                    fee => match fee.parse::<u64>() {
+                        Ok(fee) => fee,
+                        Err(_) => {
+                            self.wallet_status = Some(format!("Invalid fee: {:?}", fee));
+                            return Command::none();
+                        }
+                    },
+                };
+                let transaction = Transaction { fee, ..Transaction::new(self.my_node_id.clone(), receiver.clone(), amount) };
+                if let Err(e) = transaction.validate() {
+                    self.wallet_status = Some(format!("Cannot send: {}", e));
+                    return Command::none();
+                }
+                // 보내기 전에 잔액 검사 (멤풀에 대기 중인 내 거래만큼은 이미 쓴 것으로)
+                let available = self.available_balance();
+                if transaction.cost() > available {
+                    let error = TransactionError::InsufficientFunds {
+                        account: self.my_node_id.clone(),
+                        balance: available,
+                        amount: transaction.cost(),
+                    };
+                    self.wallet_status = Some(format!("Cannot send: {}", error));
+                    return Command::none();
+                }
+                self.wallet_status = Some(format!("Sending {} (fee {}) to {}...", amount, fee, receiver));
+                self.transaction_input.1.clear();
+                self.update(Message::TransactionSubmit(self.my_node_id.clone(), receiver, amount, fee))
+            }
+
             Message::NoMoreMessages => {
                 // 채널이 닫힌 뒤에 계속 들어오는 “더미” 메시지
                 // 특별히 할 일이 없다면 그냥 Command::none()
@@ -526,20 +764,72 @@ impl Application for BlockchainClientGUI {
             }
             Message::TransactionFinished(result) => {
                 match result {
-                    Ok(()) => println!("Transaction completed successfully!"),
-                    Err(err_msg) => eprintln!("Error submitting transaction: {}", err_msg),
+                    Ok(()) => {
+                        println!("Transaction completed successfully!");
+                        self.wallet_status = Some("Transaction accepted by the server, waiting for a block".to_string());
+                    }
+                    Err(err_msg) => {
+                        eprintln!("Error submitting transaction: {}", err_msg);
+                        self.wallet_status = Some(format!("Error submitting transaction: {}", err_msg));
+                    }
                 }
                 Command::none()
             }
             // 서버 메시지 처리: Problem
-            Message::ServerMessage(netServerMessage::Problem(problem)) => {
-                println!("Received Problem: {:?}", problem);
+            Message::ServerMessage(netServerMessage::Problem(round)) => {
+                println!("Received Problem (round {}, height {}): {:?}", round.id, round.height, round.problem);
                 // Problem 처리 로직 추가
 
-                self.current_problem = Some(problem.clone()); // 수신한 문제를 state에 저장
+                // 문제 크기에 맞춰 입력 칸을 새로 만듦
+                let size = round.problem.board().size;
+                self.solution_input = vec![vec![String::new(); size]; size];
+                // 같은 높이에서 라운드가 다시 나온 경우(만료): 대기 중이던 블록은 체인에 들어가지 않으므로 버림
+                // (높이가 올라간 경우는 합의된 블록이니 로컬 체인에 추가할 수 있게 둠)
+                if self
+                    .proposed_block
+                    .as_ref()
+                    .is_some_and(|(block, _)| block.round_id != round.id && block.index == round.height)
+                {
+                    println!("라운드 {} 시작: 검증 대기 중이던 이전 라운드 블록 폐기", round.id);
+                    self.proposed_block = None;
+                    self.proposed_round = None;
+                }
+                // 서버가 정한 원장 모델이나 체인 아이디가 내 것과 다르면 저장하고 그 규칙으로 체인을 다시 재생
+                if round.ledger != self.ledger.model() || round.chain_id != self.ledger.chain_id() {
+                    println!(
+                        "Chain changed: {} ledger on chain {} -> {} ledger on chain {}",
+                        self.ledger.model(),
+                        self.ledger.chain_id(),
+                        round.ledger,
+                        round.chain_id
+                    );
+                    self.db.save_ledger_model(round.ledger);
+                    self.db.save_chain_id(round.chain_id);
+                    self.ledger = self.db.load_ledger();
+                }
+                // 보상, 문제 규칙, 블록 용량이 바뀌면 이 라운드의 높이부터 적용하도록 기록
+                // (이미 받아들인 블록은 그때의 규칙으로 계속 검사)
+                let rules = BlockRules::of_round(&round);
+                if rules.emission != self.block_rules.emission {
+                    println!("Emission schedule changed: {} -> {}", self.block_rules.emission, rules.emission);
+                }
+                if rules.issue != self.block_rules.issue {
+                    println!("Issue rules changed: {:?} -> {:?}", self.block_rules.issue, rules.issue);
+                }
+                if rules.max_transactions != self.block_rules.max_transactions {
+                    println!("Block capacity changed: {} -> {}", self.block_rules.max_transactions, rules.max_transactions);
+                }
+                self.db.save_block_rules(round.height, rules.clone());
+                self.block_rules = rules;
+                self.current_round = Some(round); // 수신한 라운드를 state에 저장
 
                 Command::none()
             }
+            // 서버 메시지 처리: 멤풀 변경
+            Message::ServerMessage(netServerMessage::Mempool(transactions)) => {
+                self.mempool = transactions;
+                Command::none()
+            }
             Message::ReceivedProposedBlock(server_message) => todo!(),
     }
 }
@@ -556,12 +846,27 @@ impl Application for BlockchainClientGUI {
             .push(
                 1,
                 TabLabel::Text("내 정보".to_owned()),
-                view_chain_info(&self.blocks, &self.my_node_id, self.my_balance),
+                view_chain_info(
+                    &self.blocks,
+                    &self.my_node_id,
+                    &self.ledger,
+                    self.block_rules.emission,
+                    self.chain_report.as_ref(),
+                    self.timezone,
+                    view_wallet(
+                        &self.blocks,
+                        &self.mempool,
+                        &self.my_node_id,
+                        self.available_balance(),
+                        &self.transaction_input,
+                        self.wallet_status.as_deref(),
+                    ),
+                ),
             )
             .push(
                 2,
                 TabLabel::Text("블록 검증".to_owned()),
-                view_block_verification(self.blocks.last(), self.proposed_block.as_ref()),
+                view_block_verification(self.blocks.last(), self.proposed_block.as_ref(), self.timezone),
             )
             .set_active_tab(&self.active_tab);
 
@@ -1,9 +1,8 @@
 // client/src/network.rs
 
-use crate::blockchain::blockchain_db::Problem; // blockchain_db.rs에서 가져옴
+use crate::blockchain::blockchain_db::Round; // blockchain_db.rs에서 가져옴
 use crate::Block;
 use reqwest::Client;
-use serde::{Serialize, Deserialize};
 use std::error::Error;
 use tokio::sync::mpsc::{UnboundedSender, UnboundedReceiver, unbounded_channel};
 use tokio_tungstenite::connect_async;
@@ -11,44 +10,13 @@ use url::Url;
 use serde_json::Value;
 use futures::StreamExt;
 
-// 서버와 동일하게 맞춰줄 임시 구조체 (서버의 Block 구조체에 매칭)
-#[derive(Serialize)]
-pub struct BlockForServer {
-    pub index: u64,
-    pub timestamp: String,
-    pub solution: Vec<Vec<u32>>,     // 숫자 배열
-    pub problem: Problem,      // 숫자 배열
-    pub prev_solution: Vec<Vec<u32>>,
-    pub node_id: String,
-    pub data: String,
-}
-
-
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type", content = "data")]
-pub enum ServerMessage {
-    Problem(Problem),
-    Block(Block),
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ValidationResult {
-    pub is_valid: bool,
-    pub node_id: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Transaction {
-    pub sender_id: String,
-    pub receiver_id: String,
-    pub amount: u32,
-}
+// 서버와 주고받는 타입은 서버와 같은 정의를 씀
+pub use blockchain_common::protocol::{ServerMessage, Transaction, ValidationResult, PROTOCOL_VERSION};
 
 /// 실제로 서버에 POST `/submit_block` 요청을 보내는 함수
 pub async fn submit_solution_block(
     server_url: &str,
-    block_data: &BlockForServer,
+    block_data: &Block,
 ) -> Result<(), Box<dyn Error>> {
     let client = Client::new();
     let url = format!("{}/submit_block", server_url);
@@ -57,10 +25,16 @@ pub async fn submit_solution_block(
         .post(&url)
         .json(block_data)
         .send()
-        .await?
-        .error_for_status()?; // 4xx, 5xx 에러 시 Result Err 로 변환
+        .await?;
 
-    println!("Server response: {:?}", resp.text().await?);
+    // 서버가 풀이를 거절하면 어떤 규칙에서 실패했는지 본문에 담아 보내줌
+    let status = resp.status();
+    let body = resp.text().await?;
+    if !status.is_success() {
+        return Err(format!("{}: {}", status, body).into());
+    }
+
+    println!("Server response: {:?}", body);
     Ok(())
 }
 
@@ -128,6 +102,14 @@ pub async fn connect_to_websocket(
                         // JSON 데이터 역직렬화
                         match serde_json::from_str::<Value>(&text) {
                             Ok(json_value) => {
+                                // 서버가 다른 프로토콜 버전이면 메시지를 못 읽을 수 있음
+                                let version = json_value.get("version").and_then(|v| v.as_u64());
+                                if version != Some(PROTOCOL_VERSION as u64) {
+                                    eprintln!(
+                                        "Server protocol version {:?} differs from ours ({})",
+                                        version, PROTOCOL_VERSION
+                                    );
+                                }
                                 if let Some(msg_type) = json_value.get("type").and_then(|v| v.as_str()) {
                                     match msg_type {
                                         "block" => {
@@ -153,10 +135,10 @@ pub async fn connect_to_websocket(
                                         }
                                         "problem" => {
                                             if let Some(data) = json_value.get("data") {
-                                                match serde_json::from_value::<Problem>(data.clone()) {
-                                                    Ok(problem) => {
-                                                        println!("Parsed Problem: {:?}", problem);
-                                                        if let Err(e) = sender.send(ServerMessage::Problem(problem)) {
+                                                match serde_json::from_value::<Round>(data.clone()) {
+                                                    Ok(round) => {
+                                                        println!("Parsed Problem: {:?}", round);
+                                                        if let Err(e) = sender.send(ServerMessage::Problem(round)) {
                                                             eprintln!("Failed to send Problem to UI: {}", e);
                                                         }
                                                     }
@@ -166,6 +148,18 @@ pub async fn connect_to_websocket(
                                                 eprintln!("Missing 'data' field for Problem");
                                             }
                                         }
+                                        "mempool" => {
+                                            match json_value.get("data").map(|data| serde_json::from_value::<Vec<Transaction>>(data.clone())) {
+                                                Some(Ok(transactions)) => {
+                                                    println!("Mempool has {} pending transaction(s)", transactions.len());
+                                                    if let Err(e) = sender.send(ServerMessage::Mempool(transactions)) {
+                                                        eprintln!("Failed to send Mempool to UI: {}", e);
+                                                    }
+                                                }
+                                                Some(Err(e)) => eprintln!("Failed to parse Mempool: {}", e),
+                                                None => eprintln!("Missing 'data' field for Mempool"),
+                                            }
+                                        }
                                         _ => eprintln!("Unknown message type: {}", msg_type),
                                     }
                                 } else {
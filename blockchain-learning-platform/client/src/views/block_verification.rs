@@ -4,7 +4,11 @@ use iced::{
     Element, Length, Color, Border, Shadow, Theme,
 };
 use crate::Message;
-use crate::blockchain::blockchain_db::Block;
+use crate::blockchain::blockchain_db::{Block, Problem};
+use crate::blockchain::pow;
+use crate::views::short_hash;
+use crate::views::timezone::UtcOffset;
+use crate::views::transactions::transactions_table;
 
 /// 사용자 정의 스타일: 파란색 컨테이너
 struct BlueContainer;
@@ -32,6 +36,7 @@ impl From<BlueContainer> for iced::theme::Container {
 pub fn view_block_verification<'a>(
     last_block: Option<&'a Block>,
     server_block: Option<&'a (Block, bool)>,
+    timezone: UtcOffset,
 ) -> Element<'a, Message> {
     // Helper function to create a styled block container
     fn create_block_container<'a>(block_info: Column<'a, Message>) -> Container<'a, Message> {
@@ -50,18 +55,34 @@ pub fn view_block_verification<'a>(
     }
 
     // Function to build block information similar to chain_info.rs
-    fn build_block_info<'a>(block: &'a Block) -> Column<'a, Message> {
+    fn build_block_info<'a>(block: &'a Block, timezone: UtcOffset) -> Column<'a, Message> {
         // Timestamp and Node ID
         let timestamp_node_row = Row::new()
             .spacing(10)
-            .push(text(format!("Timestamp: {}", block.timestamp)))
-            .push(text(format!("Node ID: {}", block.node_id)));
+            .push(text(format!("Timestamp: {}", timezone.format(block.timestamp))))
+            .push(text(format!("Node ID: {}", block.node_id)))
+            .push(text(format!("Difficulty: {}", block.difficulty)));
 
         // Problem section
-        let problem_section = Column::new()
+        let mut problem_section = Column::new()
             .spacing(10)
-            .push(text("Problem:").size(16))
-            .push(format_matrix(&block.problem.matrix));
+            .push(text(format!("Problem ({}):", block.problem.title())).size(16))
+            .push(format_matrix(&block.problem.board().matrix));
+
+        // 작업 증명 블록: nonce 와 헤더 해시가 목표를 만족하는지 표시
+        if let Problem::ProofOfWork(_) = block.problem {
+            let hash = block.compute_hash();
+            let zero_bits = pow::leading_zero_bits(&hash);
+            problem_section = problem_section
+                .push(text(format!("Nonce: {}", block.nonce)))
+                .push(text(format!("Hash: {}", hex::encode(hash))))
+                .push(text(format!(
+                    "Leading zero bits: {} / target {} ({})",
+                    zero_bits,
+                    block.difficulty_target,
+                    if zero_bits >= block.difficulty_target { "ok" } else { "not enough work" }
+                )));
+        }
 
         // Solution section
         let solution_section = Column::new()
@@ -82,13 +103,20 @@ pub fn view_block_verification<'a>(
             .push(solution_section)
             .push(prev_solution_section);
 
-        // Data row
-        let data_row = Row::new().push(text(format!("Data: {}", block.data)));
+        // 블록 본문: 코인베이스와 거래 목록
+        let data_row = transactions_table(&block.body);
+
+        // 블록 해시와 부모 해시 (앞 16자리만 표시)
+        let hash_row = Row::new()
+            .spacing(10)
+            .push(text(format!("Hash: {}", short_hash(&block.hash))))
+            .push(text(format!("Prev Hash: {}", short_hash(&block.prev_hash))));
 
         // Combine all sections into a single column
         Column::new()
             .spacing(10)
             .push(timestamp_node_row)
+            .push(hash_row)
             .push(main_section)
             .push(data_row)
     }
@@ -97,7 +125,7 @@ pub fn view_block_verification<'a>(
     let local_section = {
         let title = text("Last Local Block").size(20);
         let content = if let Some(block) = last_block {
-            build_block_info(block)
+            build_block_info(block, timezone)
         } else {
             Column::new().push(text("There are no local blocks"))
         };
@@ -118,7 +146,13 @@ pub fn view_block_verification<'a>(
 
         if let Some((block, is_verified)) = server_block {
             // Build block info
-            let block_info = build_block_info(block)
+            // 제안된 블록이 로컬 체인 끝에 이어지는지
+            let links_to_tip = last_block.is_some_and(|last| block.prev_hash == last.hash);
+            let block_info = build_block_info(block, timezone)
+                .push(text(format!(
+                    "Parent: {}",
+                    if links_to_tip { "links to the last local block" } else { "does not link to the last local block" }
+                )))
                 .push(text(format!(
                     "Verification Status: {}",
                     if *is_verified { "Verified" } else { "Pending" }
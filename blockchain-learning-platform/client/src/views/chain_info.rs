@@ -1,10 +1,15 @@
 use iced::{
     alignment::Alignment,
-    widget::{button, container, text, Column, Row, Scrollable, Container},
+    widget::{button, container, pick_list, text, Column, Row, Scrollable, Container},
     Element, Length, Color, Border, Shadow, Theme,
 };
+use blockchain_common::emission::Emission;
+use blockchain_common::ledger::Ledger;
 use crate::Message;
 use crate::blockchain::blockchain_db::Block;
+use crate::blockchain::verify::ChainReport;
+use crate::views::timezone::{UtcOffset, OFFSETS};
+use crate::views::transactions::transactions_table;
 
 /// 사용자 정의 스타일: 파란색 컨테이너
 struct BlueContainer;
@@ -27,32 +32,39 @@ impl From<BlueContainer> for iced::theme::Container {
         iced::theme::Container::Custom(Box::new(style))
     }
 }
-/// 블록과 거래내역(트랜잭션)을 함께 표시하는 뷰
+/// 블록과 거래내역(트랜잭션)을 함께 표시하는 뷰 (`wallet`은 노드 정보 아래에 붙일 지갑 패널)
 pub fn view_chain_info<'a>(
     blocks: &'a [Block],
     node_id: &str,
-    balance: u64
+    ledger: &Ledger,
+    emission: Emission,
+    chain_report: Option<&ChainReport>,
+    timezone: UtcOffset,
+    wallet: Column<'a, Message>,
 ) -> Element<'a, Message> {
     let blocks_scrollable = Scrollable::new(
         blocks.iter().fold(Column::new().spacing(10), |col, block| {
             // Index (상단 왼쪽)
-            let index_row = Row::new().push(text(format!("Index: {}", block.index)));
+            let index_row = Row::new()
+                .spacing(10)
+                .push(text(format!("Index: {}", block.index)))
+                .push(text(format!("Difficulty: {}", block.difficulty)));
 
             // Timestamp와 Node ID (Index 아래에 가로로 배치)
             let timestamp_node_row = Row::new()
                 .spacing(10)
-                .push(text(format!("Timestamp: {}", block.timestamp)))
+                .push(text(format!("Timestamp: {}", timezone.format(block.timestamp))))
                 .push(text(format!("Node ID: {}", block.node_id)));
 
             // Problem (4x4 형태로 표시)
-            let problem_matrix = block.problem.matrix.iter().fold(Column::new().spacing(5), |col, row| {
+            let problem_matrix = block.problem.board().matrix.iter().fold(Column::new().spacing(5), |col, row| {
                 let row_text = row.iter().map(|val| format!("{}", val)).collect::<Vec<_>>().join(", ");
                 col.push(text(row_text))
             });
 
             let problem_section = Column::new()
                 .spacing(10)
-                .push(text("Problem:").size(16))
+                .push(text(format!("Problem ({}):", block.problem.title())).size(16))
                 .push(problem_matrix);
 
             // Solution (2D Vec 형태로 표시)
@@ -84,8 +96,8 @@ pub fn view_chain_info<'a>(
                 .push(solution_section)
                 .push(prev_solution_section);
 
-            // Data (가장 하단)
-            let data_row = Row::new().push(text(format!("Data: {}", block.data)));
+            // 거래 내역 표 (가장 하단)
+            let data_row = transactions_table(&block.body);
 
             // 전체 레이아웃 구성
             let block_info = Column::new()
@@ -118,8 +130,13 @@ pub fn view_chain_info<'a>(
             Row::new()
                 .spacing(10)
                 .push(button("Reset Block DB").padding(10).on_press(Message::ResetDB))
-                .push(button("Add Random Block").padding(10).on_press(Message::AddRandomBlock)),
-        );
+                .push(button("Add Random Block").padding(10).on_press(Message::AddRandomBlock))
+                .push(button("Verify Chain").padding(10).on_press(Message::VerifyChain)),
+        )
+        .push(text(chain_report.map_or_else(
+            || "Chain not verified yet".to_string(),
+            ChainReport::summary,
+        )));
 
     let node_info_section = Column::new()
     .spacing(10)
@@ -129,11 +146,28 @@ pub fn view_chain_info<'a>(
             Column::new()
                 .spacing(10)
                 .push(text(format!("NodeID: {}", node_id)))
-                .push(text(format!("Balance: {}", balance)))
+                .push(text(format!("Balance: {}", ledger.balance(node_id))))
+                .push(text(ledger_summary(ledger, node_id)))
+                .push(text(supply_summary(emission, ledger.height())))
+                // 블록 시각을 보여줄 시간대
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(text("Timezone:"))
+                        .push(pick_list(&OFFSETS[..], Some(timezone), Message::TimezoneSelected)),
+                )
         )
         .padding(10)
         .width(Length::Fill)
         .style(BlueContainer)
+    )
+    .push(text("Wallet").size(20))
+    .push(
+        Container::new(wallet)
+            .padding(10)
+            .width(Length::Fill)
+            .style(BlueContainer)
     );
 
     let info_row = Row::new()
@@ -152,3 +186,30 @@ pub fn view_chain_info<'a>(
         .height(Length::Fill)
         .into()
 }
+
+/// 발행 규칙 한 줄 요약: 체인 끝(`height`)까지의 총 발행량, 다음 블록 보상, 다음 반감 높이
+fn supply_summary(emission: Emission, height: u64) -> String {
+    let halving = emission
+        .next_halving(height)
+        .map_or_else(String::new, |next| format!(", next halving at block {}", next));
+    format!(
+        "Emission: {} (supply {}, next reward {}{})",
+        emission,
+        emission.supply(height),
+        emission.reward(height + 1),
+        halving
+    )
+}
+
+/// 원장 모델 한 줄 요약 (UTXO 원장이면 내가 가진 쓰지 않은 출력 수)
+fn ledger_summary(ledger: &Ledger, node_id: &str) -> String {
+    match ledger {
+        Ledger::Account(_) => format!("Ledger: {} (balance per account)", ledger.model()),
+        Ledger::Utxo(utxos) => format!(
+            "Ledger: {} ({} unspent output(s) of mine, {} in total)",
+            ledger.model(),
+            utxos.unspent(node_id).count(),
+            utxos.len()
+        ),
+    }
+}
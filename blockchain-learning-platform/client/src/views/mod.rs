@@ -1,3 +1,14 @@
 pub mod problem_solving;
 pub mod chain_info;
-pub mod block_verification;
\ No newline at end of file
+pub mod block_verification;
+pub mod timezone;
+pub mod transactions;
+pub mod wallet;
+
+/// 긴 hex 해시나 노드 아이디를 앞 16자리로 줄임
+pub fn short_hash(hash: &str) -> String {
+    match hash.get(..16) {
+        Some(prefix) if hash.len() > 16 => format!("{}…", prefix),
+        _ => hash.to_string(),
+    }
+}
@@ -1,9 +1,10 @@
 use iced::{
     alignment::{Alignment, Horizontal, Vertical},
-    widget::{button, column, container, text, text_input, Row, Container},
-    Element, Length, Border, Shadow, Theme,
+    widget::{button, column, container, text, text_input, Column, Row},
+    Element, Length, Border, Shadow, Theme, Color,
 };
 use crate::Message;
+use crate::blockchain::blockchain_db::{Board, Cage, Problem, Round};
 
 /// 사용자 정의 스타일: 테두리
 struct BorderStyle;
@@ -31,73 +32,66 @@ impl From<BorderStyle> for iced::theme::Container {
     }
 }
 
+/// 사용자 정의 스타일: KenKen 케이지 (케이지마다 번갈아 가며 옅은 배경색)
+struct CageStyle(usize);
+
+impl container::StyleSheet for CageStyle {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        let shade = if self.0.is_multiple_of(2) { 0.92 } else { 0.80 };
+        container::Appearance {
+            text_color: None,
+            background: Some(Color::from_rgb(shade, shade, 1.0).into()),
+            border: Border {
+                width: 1.0,
+                radius: 0.0.into(),
+                color: iced::Color::BLACK,
+            },
+            shadow: Shadow::default(),
+        }
+    }
+}
+
+impl From<CageStyle> for iced::theme::Container {
+    fn from(style: CageStyle) -> Self {
+        iced::theme::Container::Custom(Box::new(style))
+    }
+}
+
+const CELL_SIZE: f32 = 50.0;
+
 // 뷰함수
 pub fn view_problem_solving<'a>(state: &'a crate::BlockchainClientGUI) -> Element<'a, Message> {
-    let problem_matrix = if let Some(problem) = &state.current_problem {
-        &problem.matrix
-    } else {
-        // 수신한 문제가 없으면 기존에 쓰던 예시를 사용
-        &vec![
-            vec![0,0,0,0],
-            vec![0,0,0,0],
-            vec![0,0,0,0],
-            vec![0,0,0,0],
-        ]
-    };
+    // 수신한 문제가 없으면 빈 4x4 마방진을 예시로 사용
+    let problem = state
+        .current_round
+        .as_ref()
+        .map_or_else(|| Problem::empty(4), |round| round.problem.clone());
+    let round = round_label(state.current_round.as_ref());
+    if let Problem::ProofOfWork(board) = &problem {
+        return view_miner(round, board.difficulty, state.mining);
+    }
+    let board = problem.board();
+    let size = board.size;
 
     let problem_view = column![
-        text("4x4 Magic Square Problem").size(24),
-        column(
-            problem_matrix.iter().map(|row| {
-                Row::with_children(
-                    row.iter().map(|&num| {
-                        // 1) num == 0일 때 빈 문자열로 표시
-                        let display_text = if num == 0 {
-                            "".to_string() // 빈 문자열
-                        } else {
-                            num.to_string() // 숫자를 문자열로 변환
-                        };
-
-                        container(
-                            text(display_text)
-                                .width(Length::Fill)
-                                .height(Length::Fill)
-                                .horizontal_alignment(Horizontal::Center)
-                                .vertical_alignment(Vertical::Center)
-                        )
-                        .style(BorderStyle)
-                        .width(Length::Fixed(50.0))
-                        .height(Length::Fixed(50.0))
-                        .into()
-                    }).collect::<Vec<_>>()
-                )
-                .spacing(10)
-                .into()
-            })
-        ).spacing(10)
+        text(format!("{}x{} {} Problem", size, size, problem.title())).size(24),
+        text(round),
+        text(format!("Difficulty: {}", board.difficulty)),
+        text(solution_count_label(board.solution_count)),
+        text(rules(&problem)),
+        render_problem(&problem),
     ]
     .spacing(20) 
     .align_items(Alignment::Center);
 
     let solution_inputs = column![
         text("Your Solution").size(24),
-        column(
-            (0..4).map(|i| {
-                Row::with_children(
-                    (0..4).map(|j| {
-                        text_input("", &state.solution_input[i][j])
-                            .on_input(move |value| Message::InputChanged(i, j, value))
-                            .padding(5)
-                            .width(Length::Fixed(50.0))
-                            .into()
-                    }).collect::<Vec<_>>()
-                )
-                .spacing(10)
-                .into()
-            })
-        )
-        .spacing(10),
-    ];
+        solution_grid(&state.solution_input, &problem),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
 
     container(
         column![
@@ -116,3 +110,223 @@ pub fn view_problem_solving<'a>(state: &'a crate::BlockchainClientGUI) -> Elemen
     .center_y()
     .into()
 }
+
+/// 작업 증명 모드: 퍼즐 대신 nonce 를 찾는 채굴기 화면
+fn view_miner<'a>(round: String, target_bits: u32, mining: bool) -> Element<'a, Message> {
+    let mine_button = if mining {
+        button("Mining...").padding(10)
+    } else {
+        button("Start Mining").padding(10).on_press(Message::StartMining)
+    };
+
+    container(
+        column![
+            text("Proof of Work").size(24),
+            text(round),
+            text(format!("Target: {} leading zero bits", target_bits)),
+            text("Change the nonce until sha256(index, prev hash, timestamp, node id, merkle root, problem, solution, difficulty, target, nonce) reaches the target"),
+            text(format!("About {} hashes on average", 1u128 << target_bits.min(127))),
+            mine_button,
+        ]
+        .spacing(20)
+        .align_items(Alignment::Center),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x()
+    .center_y()
+    .into()
+}
+
+/// 퍼즐 종류별 규칙 안내 문구
+fn rules(problem: &Problem) -> String {
+    let n = problem.board().size;
+    match problem {
+        Problem::MagicSquare(_) => format!(
+            "Use 1..={} once each so every row, column and diagonal sums to {}",
+            n * n,
+            n * (n * n + 1) / 2
+        ),
+        Problem::LatinSquare(_) => format!("Every row and column holds 1..={} exactly once", n),
+        Problem::Sudoku(_) => {
+            let (h, w) = sudoku_box_shape(n);
+            format!("Every row, column and {}x{} box holds 1..={} exactly once", h, w, n)
+        }
+        Problem::KenKen { .. } => format!(
+            "Every row and column holds 1..={} once, and each cage must reach its target",
+            n
+        ),
+        Problem::ProofOfWork(board) => format!("Find a nonce whose header hash has {} leading zero bits", board.difficulty),
+    }
+}
+
+/// 퍼즐 종류에 맞는 문제 판 렌더러
+fn render_problem<'a>(problem: &Problem) -> Element<'a, Message> {
+    match problem {
+        Problem::MagicSquare(board) | Problem::LatinSquare(board) => render_grid(board),
+        Problem::Sudoku(board) => render_sudoku(board),
+        Problem::KenKen { board, cages } => render_kenken(board, cages),
+        Problem::ProofOfWork(board) => render_grid(board),
+    }
+}
+
+/// 숫자 하나를 담는 칸 (0은 빈 칸)
+fn given_cell<'a>(num: u32) -> Element<'a, Message> {
+    let display_text = if num == 0 { String::new() } else { num.to_string() };
+    container(
+        text(display_text)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .horizontal_alignment(Horizontal::Center)
+            .vertical_alignment(Vertical::Center)
+    )
+    .style(BorderStyle)
+    .width(Length::Fixed(CELL_SIZE))
+    .height(Length::Fixed(CELL_SIZE))
+    .into()
+}
+
+/// 마방진, 라틴 방진: 단순한 n x n 격자
+fn render_grid<'a>(board: &Board) -> Element<'a, Message> {
+    Column::with_children(board.matrix.iter().map(|row| {
+        Row::with_children(row.iter().map(|&num| given_cell(num)))
+            .spacing(10)
+            .into()
+    }))
+    .spacing(10)
+    .into()
+}
+
+/// 스도쿠: 박스 경계마다 간격을 넓혀 박스가 보이도록 배치
+fn render_sudoku<'a>(board: &Board) -> Element<'a, Message> {
+    let (box_height, box_width) = sudoku_box_shape(board.size);
+    grouped(board.size, box_height, box_width, |i, j| given_cell(board.matrix[i][j]))
+}
+
+/// KenKen: 케이지마다 배경색을 번갈아 칠하고, 케이지의 첫 칸에 목표값과 연산을 표시
+fn render_kenken<'a>(board: &Board, cages: &[Cage]) -> Element<'a, Message> {
+    let n = board.size;
+    let mut cage_of = vec![vec![0usize; n]; n];
+    let mut labels = vec![vec![String::new(); n]; n];
+    for (index, cage) in cages.iter().enumerate() {
+        for &(i, j) in &cage.cells {
+            if i < n && j < n {
+                cage_of[i][j] = index;
+            }
+        }
+        if let Some(&(i, j)) = cage.cells.iter().min() {
+            if i < n && j < n {
+                labels[i][j] = format!("{}{}", cage.target, cage.op.symbol());
+            }
+        }
+    }
+
+    Column::with_children((0..n).map(|i| {
+        Row::with_children((0..n).map(|j| {
+            let num = board.matrix[i][j];
+            let value = if num == 0 { String::new() } else { num.to_string() };
+            container(
+                column![
+                    text(labels[i][j].clone()).size(12),
+                    text(value)
+                        .width(Length::Fill)
+                        .horizontal_alignment(Horizontal::Center),
+                ]
+            )
+            .style(CageStyle(cage_of[i][j]))
+            .padding(2)
+            .width(Length::Fixed(CELL_SIZE))
+            .height(Length::Fixed(CELL_SIZE))
+            .into()
+        }))
+        .spacing(2)
+        .into()
+    }))
+    .spacing(2)
+    .into()
+}
+
+/// 퍼즐 종류에 맞는 풀이 입력 격자
+///
+/// 스도쿠는 박스 단위로 묶어 보여주고, 나머지는 n x n 격자. 자리표시자로 입력 범위를 안내
+fn solution_grid<'a>(inputs: &'a [Vec<String>], problem: &Problem) -> Element<'a, Message> {
+    let n = inputs.len();
+    let max = match problem {
+        Problem::MagicSquare(_) => n * n,
+        _ => n,
+    };
+    let placeholder = format!("1-{}", max);
+    let input = move |i: usize, j: usize| -> Element<'a, Message> {
+        text_input(&placeholder, &inputs[i][j])
+            .on_input(move |value| Message::InputChanged(i, j, value))
+            .padding(5)
+            .width(Length::Fixed(CELL_SIZE))
+            .into()
+    };
+
+    match problem {
+        Problem::Sudoku(_) => {
+            let (box_height, box_width) = sudoku_box_shape(n);
+            grouped(n, box_height, box_width, input)
+        }
+        _ => Column::with_children((0..n).map(|i| {
+            Row::with_children((0..n).map(|j| input(i, j)))
+                .spacing(10)
+                .into()
+        }))
+        .spacing(10)
+        .into(),
+    }
+}
+
+/// box_height x box_width 박스 단위로 간격을 둔 n x n 격자
+fn grouped<'a>(
+    n: usize,
+    box_height: usize,
+    box_width: usize,
+    cell: impl Fn(usize, usize) -> Element<'a, Message>,
+) -> Element<'a, Message> {
+    Column::with_children((0..n).step_by(box_height.max(1)).map(|top| {
+        Column::with_children((top..(top + box_height).min(n)).map(|i| {
+            Row::with_children((0..n).step_by(box_width.max(1)).map(|left| {
+                Row::with_children((left..(left + box_width).min(n)).map(|j| cell(i, j)))
+                    .spacing(4)
+                    .into()
+            }))
+            .spacing(16)
+            .into()
+        }))
+        .spacing(4)
+        .into()
+    }))
+    .spacing(16)
+    .into()
+}
+
+/// 서버와 같은 규칙: 높이는 √n 이하의 가장 큰 약수
+fn sudoku_box_shape(n: usize) -> (usize, usize) {
+    let height = (2..=n)
+        .take_while(|d| d * d <= n)
+        .filter(|&d| n.is_multiple_of(d))
+        .last()
+        .unwrap_or(1);
+    (height, n / height)
+}
+
+/// 지금 풀고 있는 라운드 안내 (이 번호로 제출해야 서버가 받아 줌)
+fn round_label(round: Option<&Round>) -> String {
+    match round {
+        Some(round) => format!("Round #{} · Block height {}", round.id, round.height),
+        None => "Waiting for the server to start a round".to_string(),
+    }
+}
+
+/// 서버가 기록한 풀이 수 안내
+fn solution_count_label(solution_count: Option<u32>) -> String {
+    match solution_count {
+        Some(1) => "Unique solution".to_string(),
+        Some(0) => "No solution".to_string(),
+        Some(count) => format!("{} valid solutions (any of them is accepted)", count),
+        None => "Multiple solutions (count unknown)".to_string(),
+    }
+}
@@ -0,0 +1,50 @@
+// client/src/views/timezone.rs
+
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset};
+
+/// 화면에 블록 시각을 보여줄 때 쓰는 UTC 기준 시차 (시간 단위)
+/// 블록에는 항상 UTC 유닉스 시각(밀리초)이 저장되고, 현지 시각으로 바꾸는 것은 화면뿐이다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtcOffset(pub i32);
+
+impl UtcOffset {
+    /// 한국 시간 (기본값)
+    pub const KST: UtcOffset = UtcOffset(9);
+
+    /// 고를 수 있는 시차: UTC-12 ~ UTC+14
+    pub const ALL: [UtcOffset; 27] = {
+        let mut all = [UtcOffset(0); 27];
+        let mut i = 0;
+        while i < all.len() {
+            all[i] = UtcOffset(i as i32 - 12);
+            i += 1;
+        }
+        all
+    };
+
+    /// 블록 시각(UTC 밀리초)을 이 시차의 `YYYY-MM-DD HH:MM:SS` 로 표시
+    pub fn format(self, timestamp_ms: u64) -> String {
+        let offset = FixedOffset::east_opt(self.0 * 3600).expect("offset within a day");
+        match DateTime::from_timestamp_millis(timestamp_ms as i64) {
+            Some(datetime) => datetime.with_timezone(&offset).format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => format!("{} ms", timestamp_ms),
+        }
+    }
+}
+
+impl Default for UtcOffset {
+    fn default() -> Self {
+        UtcOffset::KST
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UTC{:+03}:00", self.0)
+    }
+}
+
+/// 시차 선택 목록 (`pick_list`에 넘길 `'static` 슬라이스)
+pub static OFFSETS: [UtcOffset; 27] = UtcOffset::ALL;
@@ -0,0 +1,39 @@
+// client/src/views/transactions.rs
+
+use iced::{
+    widget::{text, Column, Row},
+    Length,
+};
+use crate::Message;
+use crate::blockchain::blockchain_db::BlockBody;
+use crate::views::short_hash;
+
+/// 블록 본문을 표로 표시: 첫 줄은 코인베이스 보상, 그 아래로 거래
+pub fn transactions_table<'a>(body: &BlockBody) -> Column<'a, Message> {
+    let coinbase = &body.coinbase;
+    body.transactions.iter().fold(
+        Column::new()
+            .spacing(5)
+            .push(text(format!("Transactions ({} + coinbase):", body.transactions.len())).size(16))
+            .push(table_row("From", "To", "Amount", "Fee"))
+            .push(table_row("(coinbase)", &short_hash(&coinbase.receiver_id), &coinbase.amount.to_string(), "")),
+        |table, tx| {
+            table.push(table_row(
+                &short_hash(&tx.sender_id),
+                &short_hash(&tx.receiver_id),
+                &tx.amount.to_string(),
+                &tx.fee.to_string(),
+            ))
+        },
+    )
+}
+
+/// 표 한 줄 (보내는 쪽, 받는 쪽, 금액, 수수료)
+fn table_row<'a>(from: &str, to: &str, amount: &str, fee: &str) -> Row<'a, Message> {
+    Row::new()
+        .spacing(10)
+        .push(text(from.to_string()).width(Length::FillPortion(2)))
+        .push(text(to.to_string()).width(Length::FillPortion(2)))
+        .push(text(amount.to_string()).width(Length::FillPortion(1)))
+        .push(text(fee.to_string()).width(Length::FillPortion(1)))
+}
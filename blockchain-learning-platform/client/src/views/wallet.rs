@@ -0,0 +1,101 @@
+// client/src/views/wallet.rs
+
+use iced::{
+    alignment::Alignment,
+    widget::{button, text, text_input, Column, Row, Scrollable},
+    Length,
+};
+use crate::Message;
+use crate::blockchain::blockchain_db::{Block, Transaction};
+use crate::blockchain::fees::{estimate_fee, FEE_ESTIMATE_BLOCKS};
+use crate::blockchain::history::{wallet_history, Direction, HistoryEntry, TxStatus};
+use crate::views::short_hash;
+
+/// 지갑: 보낼 수 있는 금액, 보내기 양식(받는 쪽, 금액, 수수료), 마지막 보내기 결과, 보내고 받은 거래 내역
+///
+/// `input`은 `(받는 쪽, 금액, 수수료)` 입력 칸. 수수료를 비워 두면 추천 수수료로 보낸다.
+pub fn view_wallet<'a>(
+    blocks: &[Block],
+    mempool: &[Transaction],
+    node_id: &str,
+    available: u64,
+    input: &(String, String, String),
+    status: Option<&str>,
+) -> Column<'a, Message> {
+    let (receiver, amount, fee) = input;
+    let suggested_fee = estimate_fee(blocks);
+
+    let form = Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(
+            text_input("Recipient node id", receiver)
+                .on_input(Message::RecipientChanged)
+                .padding(5)
+                .width(Length::FillPortion(4)),
+        )
+        .push(
+            text_input("Amount", amount)
+                .on_input(Message::AmountChanged)
+                .padding(5)
+                .width(Length::FillPortion(1)),
+        )
+        .push(
+            text_input(&format!("Fee ({})", suggested_fee), fee)
+                .on_input(Message::FeeChanged)
+                .padding(5)
+                .width(Length::FillPortion(1)),
+        )
+        .push(button("Send").padding(5).on_press(Message::SendCoins));
+
+    let history = wallet_history(blocks, mempool, node_id);
+    let history_table = Scrollable::new(history.iter().fold(
+        Column::new()
+            .spacing(5)
+            .push(history_row("Status", "", "Counterparty", "Amount", "Fee")),
+        |table, entry| table.push(entry_row(entry)),
+    ))
+    .height(Length::Fixed(200.0));
+
+    Column::new()
+        .spacing(10)
+        .push(text(format!("Available to send: {}", available)))
+        .push(form)
+        .push(text(format!(
+            "Suggested fee: {} (median of the last {} blocks), pending transactions (server mempool): {}",
+            suggested_fee,
+            FEE_ESTIMATE_BLOCKS,
+            mempool.len()
+        )))
+        .push(text(status.unwrap_or_default().to_string()))
+        .push(text(format!("History ({} transaction(s)):", history.len())).size(16))
+        .push(history_table)
+}
+
+/// 내역 한 줄: 상태, 방향, 상대, 금액, 수수료
+fn entry_row<'a>(entry: &HistoryEntry) -> Row<'a, Message> {
+    let status = match entry.status {
+        TxStatus::Pending => "pending".to_string(),
+        TxStatus::Confirmed(index) => format!("confirmed (#{})", index),
+    };
+    let direction = match entry.direction {
+        Direction::Sent => "sent",
+        Direction::Received => "received",
+        Direction::Reward => "reward",
+    };
+    let fee = match entry.direction {
+        Direction::Sent => entry.fee.to_string(),
+        _ => String::new(),
+    };
+    history_row(&status, direction, &short_hash(&entry.counterparty), &entry.amount.to_string(), &fee)
+}
+
+fn history_row<'a>(status: &str, direction: &str, counterparty: &str, amount: &str, fee: &str) -> Row<'a, Message> {
+    Row::new()
+        .spacing(10)
+        .push(text(status.to_string()).width(Length::FillPortion(2)))
+        .push(text(direction.to_string()).width(Length::FillPortion(1)))
+        .push(text(counterparty.to_string()).width(Length::FillPortion(2)))
+        .push(text(amount.to_string()).width(Length::FillPortion(1)))
+        .push(text(fee.to_string()).width(Length::FillPortion(1)))
+}
@@ -0,0 +1,230 @@
+// common/src/accounts.rs
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::body::{BlockBody, Transaction, TransactionError};
+use crate::chain::{default_chain_id, BlockError, DEFAULT_CHAIN_ID};
+use crate::ledger::LedgerModel;
+use crate::protocol::Block;
+
+/// 블록의 보상과 거래를 제네시스부터 차례로 반영한 계정 잔액
+///
+/// 서버와 클라이언트가 같은 규칙으로 잔액을 계산하므로, 같은 체인을 가진 노드는 같은 잔액을 본다.
+/// 계정마다 다음 거래가 써야 할 nonce 도 기록해, 이미 반영한 거래를 다시 넣을 수 없다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub height: u64, // 마지막으로 반영한 블록 높이 (제네시스는 보상이 없으므로 0 에서 시작)
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u32, // 이 체인의 거래만 반영
+    balances: BTreeMap<String, u64>,
+    #[serde(default)]
+    nonces: BTreeMap<String, u64>, // 계정마다 다음 거래의 nonce (없으면 0)
+}
+
+impl Default for AccountState {
+    fn default() -> Self {
+        AccountState::new(DEFAULT_CHAIN_ID)
+    }
+}
+
+impl AccountState {
+    /// 아무 블록도 반영하지 않은 `chain_id` 체인의 잔액
+    pub fn new(chain_id: u32) -> Self {
+        AccountState { height: 0, chain_id, balances: BTreeMap::new(), nonces: BTreeMap::new() }
+    }
+
+    /// 블록들을 높이 순서대로 반영 (제네시스는 건너뜀)
+    /// 규칙을 어긴 블록이 있으면 그 블록의 높이와 이유를 돌려줌
+    pub fn replay<'a>(chain_id: u32, blocks: impl IntoIterator<Item = &'a Block>) -> Result<Self, (u64, BlockError)> {
+        let mut state = AccountState::new(chain_id);
+        for block in blocks.into_iter().filter(|block| block.index > 0) {
+            state.apply_block(block).map_err(|e| (block.index, e))?;
+        }
+        Ok(state)
+    }
+
+    pub fn balance(&self, account: &str) -> u64 {
+        self.balances.get(account).copied().unwrap_or(0)
+    }
+
+    /// `account`가 다음에 보낼 거래의 nonce
+    pub fn next_nonce(&self, account: &str) -> u64 {
+        self.nonces.get(account).copied().unwrap_or(0)
+    }
+
+    /// 잔액이 있는 계정 전체 (아이디 순)
+    pub fn balances(&self) -> &BTreeMap<String, u64> {
+        &self.balances
+    }
+
+    /// 체인 끝 다음 블록을 반영 (실패하면 아무것도 바꾸지 않음)
+    pub fn apply_block(&mut self, block: &Block) -> Result<(), BlockError> {
+        let expected = self.height + 1;
+        if block.index != expected {
+            return Err(BlockError::WrongHeight { expected, actual: block.index });
+        }
+        let mut next = self.clone();
+        next.apply_body(&block.body)?;
+        next.height = block.index;
+        *self = next;
+        Ok(())
+    }
+
+    /// 블록을 반영할 수 있는지만 확인
+    pub fn check_block(&self, block: &Block) -> Result<(), BlockError> {
+        self.clone().apply_block(block)
+    }
+
+    /// 코인베이스를 먼저 더하고 거래를 순서대로 반영
+    fn apply_body(&mut self, body: &BlockBody) -> Result<(), BlockError> {
+        self.credit(&body.coinbase.receiver_id, body.coinbase.amount);
+        for (index, transaction) in body.transactions.iter().enumerate() {
+            self.apply_transaction(transaction)
+                .map_err(|error| BlockError::InvalidTransaction { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// 거래 하나를 반영: 다른 체인의 거래, 순서가 맞지 않는 nonce, 보내는 쪽 잔액이 금액과 수수료에 모자란 거래는 거절
+    /// 수수료는 보내는 쪽에서 빠지기만 하고, 블록을 만든 노드는 코인베이스로 받는다.
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> Result<(), TransactionError> {
+        transaction.validate()?;
+        // 출력을 쓰는 거래는 UTXO 원장의 거래
+        if !transaction.inputs.is_empty() {
+            return Err(TransactionError::WrongLedger { ledger: LedgerModel::Account });
+        }
+        if transaction.chain_id != self.chain_id {
+            return Err(TransactionError::WrongChain { expected: self.chain_id, actual: transaction.chain_id });
+        }
+        let nonce = self.next_nonce(&transaction.sender_id);
+        if transaction.nonce != nonce {
+            return Err(TransactionError::BadNonce {
+                account: transaction.sender_id.clone(),
+                expected: nonce,
+                actual: transaction.nonce,
+            });
+        }
+        let balance = self.balance(&transaction.sender_id);
+        let cost = transaction.cost();
+        if balance < cost {
+            return Err(TransactionError::InsufficientFunds {
+                account: transaction.sender_id.clone(),
+                balance,
+                amount: cost,
+            });
+        }
+        self.balances.insert(transaction.sender_id.clone(), balance - cost);
+        self.nonces.insert(transaction.sender_id.clone(), nonce + 1);
+        self.credit(&transaction.receiver_id, transaction.amount);
+        Ok(())
+    }
+
+    fn credit(&mut self, account: &str, amount: u64) {
+        if amount > 0 {
+            let balance = self.balances.entry(account.to_string()).or_insert(0);
+            *balance = balance.saturating_add(amount);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BLOCK_REWARD;
+    use crate::test_support::{block, block_with_body};
+
+    fn tx(sender: &str, receiver: &str, amount: u64) -> Transaction {
+        Transaction::new(sender, receiver, amount)
+    }
+
+    #[test]
+    fn replays_rewards_and_transfers() {
+        let chain = [
+            Block::genesis(),
+            block(1, "alice", vec![]),
+            block(2, "bob", vec![tx("alice", "bob", 4), tx("bob", "carol", 12)]),
+        ];
+        let state = AccountState::replay(DEFAULT_CHAIN_ID, &chain).unwrap();
+        assert_eq!(state.height, 2);
+        assert_eq!(state.balance("alice"), 6);
+        assert_eq!(state.balance("bob"), 2);
+        assert_eq!(state.balance("carol"), 12);
+        assert_eq!(state.balance("nobody"), 0);
+
+        // 한 블록씩 반영해도 같은 결과
+        let mut incremental = AccountState::default();
+        for block in &chain[1..] {
+            incremental.apply_block(block).unwrap();
+        }
+        assert_eq!(incremental, state);
+    }
+
+    #[test]
+    fn overdrafts_reject_the_whole_block() {
+        let mut state = AccountState::replay(DEFAULT_CHAIN_ID, &[Block::genesis(), block(1, "alice", vec![])]).unwrap();
+        let before = state.clone();
+
+        let overdraft = block(2, "bob", vec![tx("bob", "alice", 5), tx("alice", "carol", 16)]);
+        assert_eq!(
+            state.apply_block(&overdraft),
+            Err(BlockError::InvalidTransaction {
+                index: 1,
+                error: TransactionError::InsufficientFunds { account: "alice".to_string(), balance: 15, amount: 16 },
+            })
+        );
+        assert_eq!(state, before);
+
+        assert_eq!(
+            state.apply_block(&block(3, "bob", vec![])),
+            Err(BlockError::WrongHeight { expected: 2, actual: 3 })
+        );
+    }
+
+    #[test]
+    fn nonces_and_chain_ids_stop_replays() {
+        let mut state = AccountState::replay(DEFAULT_CHAIN_ID, &[Block::genesis(), block(1, "alice", vec![])]).unwrap();
+        let first = tx("alice", "bob", 1);
+        state.apply_transaction(&first).unwrap();
+        assert_eq!(state.next_nonce("alice"), 1);
+        assert_eq!(state.next_nonce("bob"), 0);
+
+        // 같은 거래를 다시 넣으면 nonce 가 맞지 않음
+        assert_eq!(
+            state.apply_transaction(&first),
+            Err(TransactionError::BadNonce { account: "alice".to_string(), expected: 1, actual: 0 })
+        );
+        let second = Transaction { nonce: 1, ..first.clone() };
+        let elsewhere = Transaction { chain_id: DEFAULT_CHAIN_ID + 1, ..second.clone() };
+        assert_eq!(
+            state.apply_transaction(&elsewhere),
+            Err(TransactionError::WrongChain { expected: DEFAULT_CHAIN_ID, actual: DEFAULT_CHAIN_ID + 1 })
+        );
+        state.apply_transaction(&second).unwrap();
+        assert_eq!(state.balance("alice"), 8);
+
+        // 다른 체인의 잔액에는 이 체인의 거래가 들어가지 않음
+        let mut other = AccountState::new(7);
+        other.apply_block(&block(1, "alice", vec![])).unwrap();
+        assert!(matches!(other.apply_transaction(&first), Err(TransactionError::WrongChain { expected: 7, .. })));
+    }
+
+    #[test]
+    fn fees_move_from_the_sender_to_the_miner() {
+        let mut state = AccountState::replay(DEFAULT_CHAIN_ID, &[Block::genesis(), block(1, "alice", vec![])]).unwrap();
+        let paid = Transaction { fee: 3, ..tx("alice", "bob", 4) };
+        let mined = block_with_body(2, "carol", BlockBody::with_fees("carol", BLOCK_REWARD, vec![paid.clone()]));
+        state.apply_block(&mined).unwrap();
+        assert_eq!(state.balance("alice"), 3);
+        assert_eq!(state.balance("bob"), 4);
+        assert_eq!(state.balance("carol"), BLOCK_REWARD + 3);
+
+        // 금액은 남은 잔액 안이어도 수수료까지는 낼 수 없음
+        let too_much = Transaction { nonce: 1, fee: 1, ..tx("alice", "bob", 3) };
+        assert_eq!(
+            state.apply_transaction(&too_much),
+            Err(TransactionError::InsufficientFunds { account: "alice".to_string(), balance: 3, amount: 4 })
+        );
+    }
+}
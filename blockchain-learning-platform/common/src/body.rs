@@ -0,0 +1,293 @@
+// common/src/body.rs
+
+//! 블록 본문: 코인베이스 보상 한 건과 거래 목록, 그리고 헤더에 들어가는 머클 루트
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::chain::{default_chain_id, DEFAULT_CHAIN_ID};
+use crate::header::Encoder;
+use crate::ledger::LedgerModel;
+use crate::utxo::OutPoint;
+
+/// 블록을 만든 노드가 받는 보상
+pub const BLOCK_REWARD: u64 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub sender_id: String,
+    pub receiver_id: String,
+    pub amount: u64,
+    /// 블록을 만든 노드에게 주는 수수료 (보내는 쪽이 `amount`와 함께 냄, 코인베이스로 받음)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub fee: u64,
+    /// 보내는 계정이 낸 거래 순번 (0 부터, 계정 원장에서 같은 거래를 다시 넣지 못하게 함)
+    #[serde(default)]
+    pub nonce: u64,
+    /// 이 거래를 낸 체인 (`Round::chain_id`)
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u32,
+    /// UTXO 원장: 이 거래가 쓰는 `sender_id`의 출력 (계정 원장에서는 비어 있음)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<OutPoint>,
+    /// `sender_id`의 키로 `hash()`에 한 서명 (hex, `identity::sign_transaction`)
+    /// 서명 대상이 해시이므로 해시와 머클 루트에는 들어가지 않는다.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub signature: String,
+}
+
+/// 거래가 어긴 규칙
+///
+/// 서버는 `/transaction`에서 거절할 때 `{"status": "rejected", "reason": {"rule": ...}}`로 돌려준다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum TransactionError {
+    #[error("sender and receiver ids must not be empty")]
+    MissingParty,
+    #[error("{id} cannot send to itself")]
+    SelfTransfer { id: String },
+    #[error("amount must be positive")]
+    ZeroAmount,
+    #[error("{account} has {balance}, cannot send {amount}")]
+    InsufficientFunds { account: String, balance: u64, amount: u64 },
+    #[error("transaction {hash} is already pending")]
+    Duplicate { hash: String },
+    #[error("mempool is full ({capacity} transactions)")]
+    MempoolFull { capacity: usize },
+    #[error("transaction is for chain {actual}, not chain {expected}")]
+    WrongChain { expected: u32, actual: u32 },
+    #[error("{account} must use nonce {expected}, not {actual}")]
+    BadNonce { account: String, expected: u64, actual: u64 },
+    #[error("transaction does not fit the {ledger} ledger")]
+    WrongLedger { ledger: LedgerModel },
+    #[error("output {outpoint} is unknown or already spent")]
+    InputNotUnspent { outpoint: OutPoint },
+    #[error("output {outpoint} is spent twice")]
+    DoubleSpend { outpoint: OutPoint },
+    #[error("output {outpoint} belongs to {owner}")]
+    NotOwner { outpoint: OutPoint, owner: String },
+    #[error("transaction is not signed by {account}")]
+    BadSignature { account: String },
+}
+
+/// 블록 보상: 보내는 쪽 없이 새로 생기는 코인
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coinbase {
+    pub receiver_id: String,
+    pub amount: u64,
+}
+
+/// 블록 본문 (헤더에는 `merkle_root()`만 들어감)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockBody {
+    pub coinbase: Coinbase,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+impl Transaction {
+    /// 기본 체인에서 nonce 0 으로 보내는 계정 원장의 거래 (쓰는 출력 없음, 서명 전)
+    pub fn new(sender_id: impl Into<String>, receiver_id: impl Into<String>, amount: u64) -> Self {
+        Transaction {
+            sender_id: sender_id.into(),
+            receiver_id: receiver_id.into(),
+            amount,
+            fee: 0,
+            nonce: 0,
+            chain_id: DEFAULT_CHAIN_ID,
+            inputs: Vec::new(),
+            signature: String::new(),
+        }
+    }
+
+    /// 머클 트리의 잎: sha256(0x00, "tx", sender_id, receiver_id, amount, chain_id, nonce[, inputs][, "fee", fee])
+    /// 쓰는 출력이 없거나 수수료가 0 이면 넣지 않는다 (수수료가 없던 거래의 해시가 그대로 남음).
+    pub fn hash(&self) -> [u8; 32] {
+        let mut encoder = Encoder::leaf("tx");
+        encoder.str(&self.sender_id);
+        encoder.str(&self.receiver_id);
+        encoder.u64(self.amount);
+        encoder.u32(self.chain_id);
+        encoder.u64(self.nonce);
+        if !self.inputs.is_empty() {
+            encoder.u64(self.inputs.len() as u64);
+            for input in &self.inputs {
+                encoder.str(&input.txid);
+                encoder.u32(input.vout);
+            }
+        }
+        if self.fee > 0 {
+            encoder.str("fee");
+            encoder.u64(self.fee);
+        }
+        encoder.finish()
+    }
+
+    /// 보내는 쪽이 내는 금액 (받는 쪽에게 `amount`, 블록을 만든 노드에게 `fee`)
+    pub fn cost(&self) -> u64 {
+        self.amount.saturating_add(self.fee)
+    }
+
+    /// 누구의 잔액과도 상관없이 거래 하나만 보고 알 수 있는 규칙
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if self.sender_id.trim().is_empty() || self.receiver_id.trim().is_empty() {
+            return Err(TransactionError::MissingParty);
+        }
+        if self.sender_id == self.receiver_id {
+            return Err(TransactionError::SelfTransfer { id: self.sender_id.clone() });
+        }
+        if self.amount == 0 {
+            return Err(TransactionError::ZeroAmount);
+        }
+        Ok(())
+    }
+}
+
+impl Coinbase {
+    /// 머클 트리의 잎: sha256(0x00, "coinbase", receiver_id, amount)
+    pub fn hash(&self) -> [u8; 32] {
+        let mut encoder = Encoder::leaf("coinbase");
+        encoder.str(&self.receiver_id);
+        encoder.u64(self.amount);
+        encoder.finish()
+    }
+}
+
+impl BlockBody {
+    /// 거래 없이 보상만 담은 본문
+    pub fn reward(receiver_id: impl Into<String>, amount: u64) -> Self {
+        BlockBody {
+            coinbase: Coinbase { receiver_id: receiver_id.into(), amount },
+            transactions: Vec::new(),
+        }
+    }
+
+    /// 보상 `reward`에 거래 수수료를 모두 더한 코인베이스와 거래들
+    pub fn with_fees(receiver_id: impl Into<String>, reward: u64, transactions: Vec<Transaction>) -> Self {
+        let mut body = BlockBody { transactions, ..BlockBody::reward(receiver_id, reward) };
+        body.coinbase.amount = reward.saturating_add(body.fees());
+        body
+    }
+
+    /// 거래 수수료의 합 (블록을 만든 노드가 코인베이스로 받음)
+    pub fn fees(&self) -> u64 {
+        self.transactions.iter().fold(0, |fees: u64, transaction| fees.saturating_add(transaction.fee))
+    }
+
+    /// 코인베이스를 맨 앞에, 거래를 순서대로 잎으로 둔 머클 트리의 루트
+    pub fn merkle_root(&self) -> [u8; 32] {
+        let leaves: Vec<[u8; 32]> = std::iter::once(self.coinbase.hash())
+            .chain(self.transactions.iter().map(Transaction::hash))
+            .collect();
+        merkle_root(&leaves)
+    }
+
+    /// 헤더에 적는 hex 머클 루트
+    pub fn merkle_root_hex(&self) -> String {
+        hex::encode(self.merkle_root())
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// 비트코인처럼 두 개씩 묶어 올라가는 머클 루트 (홀수 개면 마지막 것을 한 번 더 씀)
+///
+/// 부모 = sha256(0x01, 왼쪽, 오른쪽). 잎은 0x00 으로 시작하므로 부모를 잎으로 위장할 수 없다.
+/// 잎이 없으면 0 으로 채운 32바이트.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let (left, right) = (pair[0], *pair.last().unwrap());
+                let mut hasher = Sha256::new();
+                hasher.update([1]);
+                hasher.update(left);
+                hasher.update(right);
+                hasher.finalize().into()
+            })
+            .collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(sender: &str, receiver: &str, amount: u64) -> Transaction {
+        Transaction::new(sender, receiver, amount)
+    }
+
+    #[test]
+    fn merkle_root_commits_to_every_transaction_and_their_order() {
+        let mut body = BlockBody::reward("miner", BLOCK_REWARD);
+        let reward_only = body.merkle_root();
+        assert_eq!(reward_only, body.coinbase.hash());
+
+        body.transactions = vec![tx("a", "b", 1), tx("b", "c", 2), tx("c", "a", 3)];
+        let root = body.merkle_root();
+        assert_ne!(root, reward_only);
+
+        let mut changed = body.clone();
+        changed.transactions[2].amount = 4;
+        assert_ne!(changed.merkle_root(), root);
+
+        let mut reordered = body.clone();
+        reordered.transactions.swap(0, 1);
+        assert_ne!(reordered.merkle_root(), root);
+
+        let mut next_nonce = body.clone();
+        next_nonce.transactions[0].nonce = 1;
+        assert_ne!(next_nonce.merkle_root(), root);
+
+        let mut with_fee = body.clone();
+        with_fee.transactions[1].fee = 1;
+        assert_ne!(with_fee.merkle_root(), root);
+
+        let mut other_chain = body.clone();
+        other_chain.transactions[0].chain_id = 2;
+        assert_ne!(other_chain.merkle_root(), root);
+
+        let mut with_inputs = body.clone();
+        with_inputs.transactions[0].inputs = vec![OutPoint { txid: "ab".repeat(32), vout: 1 }];
+        assert_ne!(with_inputs.merkle_root(), root);
+
+        let mut other_miner = body.clone();
+        other_miner.coinbase.receiver_id = "thief".to_string();
+        assert_ne!(other_miner.merkle_root(), root);
+    }
+
+    #[test]
+    fn transactions_need_two_parties_and_an_amount() {
+        assert_eq!(tx("a", "b", 1).validate(), Ok(()));
+        assert_eq!(tx("", "b", 1).validate(), Err(TransactionError::MissingParty));
+        assert_eq!(tx("a", " ", 1).validate(), Err(TransactionError::MissingParty));
+        assert_eq!(tx("a", "a", 1).validate(), Err(TransactionError::SelfTransfer { id: "a".to_string() }));
+        assert_eq!(tx("a", "b", 0).validate(), Err(TransactionError::ZeroAmount));
+    }
+
+    #[test]
+    fn miners_collect_the_fees() {
+        let transactions = vec![Transaction { fee: 2, ..tx("a", "b", 5) }, tx("b", "c", 1), Transaction { fee: 3, ..tx("c", "a", 1) }];
+        assert_eq!(transactions[0].cost(), 7);
+        let body = BlockBody::with_fees("miner", BLOCK_REWARD, transactions);
+        assert_eq!(body.fees(), 5);
+        assert_eq!(body.coinbase.amount, BLOCK_REWARD + 5);
+        assert_eq!(BlockBody::with_fees("miner", 0, vec![]), BlockBody::reward("miner", 0));
+    }
+
+    #[test]
+    fn odd_levels_repeat_the_last_node() {
+        let leaves = [[1; 32], [2; 32], [3; 32]];
+        assert_eq!(merkle_root(&leaves), merkle_root(&[[1; 32], [2; 32], [3; 32], [3; 32]]));
+        assert_eq!(merkle_root(&[]), [0; 32]);
+    }
+}
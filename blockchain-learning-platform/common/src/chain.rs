@@ -0,0 +1,340 @@
+// common/src/chain.rs
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::body::{BlockBody, TransactionError};
+use crate::header::BlockHeader;
+use crate::identity::SignatureError;
+use crate::pow::work_problem;
+use crate::problem::Problem;
+use crate::puzzles::{GenerateParams, PuzzleKind, SolutionError};
+
+// 제네시스 블록: 모든 노드가 같은 해시에서 시작하도록 고정된 헤더
+/// 2025-01-01 00:00:00 UTC (유닉스 시각, 밀리초)
+pub const GENESIS_TIMESTAMP: u64 = 1_735_689_600_000;
+pub const GENESIS_NODE_ID: &str = "GenesisNode";
+/// 제네시스 블록의 부모 해시 (0 으로 채운 64자리 hex)
+pub const GENESIS_PREV_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 서버가 `CHAIN_ID`를 정하지 않았을 때의 체인 아이디
+/// 거래에 체인 아이디를 넣어 다른 체인(같은 제네시스로 띄운 다른 서버)의 거래를 다시 쓸 수 없게 한다.
+pub const DEFAULT_CHAIN_ID: u32 = 1;
+
+/// serde 기본값용
+pub fn default_chain_id() -> u32 {
+    DEFAULT_CHAIN_ID
+}
+
+/// 서버가 `MAX_BLOCK_TRANSACTIONS`를 정하지 않았을 때의 블록 용량 (코인베이스를 뺀 거래 수)
+pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: usize = 100;
+
+/// serde 기본값용
+pub fn default_max_block_transactions() -> usize {
+    DEFAULT_MAX_BLOCK_TRANSACTIONS
+}
+
+/// 블록 시각이 검사하는 쪽의 시계보다 앞서도 되는 기본 허용치 (밀리초)
+pub const DEFAULT_MAX_CLOCK_SKEW_MS: u64 = 60_000;
+
+/// 퍼즐 종류를 고를 때 쓰는 난수 용도
+pub const KIND_DOMAIN: &str = "kind";
+/// 문제 판을 만들 때 쓰는 난수 용도
+pub const PUZZLE_DOMAIN: &str = "puzzle";
+
+/// 블록이 체인 규칙(위치, 해시, 본문, 보상, 시각, 서명, 풀이)을 어긴 이유
+///
+/// 서명과 풀이 오류는 감싸지 않고 그대로 직렬화되어 `{"rule": "bad_signature"}`, `{"rule": "row_sum", ...}` 모양이 된다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum BlockError {
+    #[error("block is at height {actual}, the chain expects {expected}")]
+    WrongHeight { expected: u64, actual: u64 },
+    #[error("problem was not issued for height {height}")]
+    ProblemNotIssued { height: u64 },
+    #[error("block difficulty {actual} is not the issued difficulty {expected}")]
+    WrongDifficulty { expected: u32, actual: u32 },
+    #[error("block links to parent {actual}, the chain tip is {expected}")]
+    PrevHashMismatch { expected: String, actual: String },
+    #[error("block hash {actual} does not match its header, which hashes to {expected}")]
+    HashMismatch { expected: String, actual: String },
+    #[error("merkle root {actual} does not match the block body, which hashes to {expected}")]
+    MerkleRootMismatch { expected: String, actual: String },
+    #[error("coinbase pays {receiver}, not the miner {miner}")]
+    CoinbaseNotToMiner { miner: String, receiver: String },
+    #[error("coinbase of block {height} is {actual}, the emission schedule and fees allow {expected}")]
+    WrongReward { height: u64, expected: u64, actual: u64 },
+    #[error("transaction {index} is invalid: {error}")]
+    InvalidTransaction { index: usize, error: TransactionError },
+    #[error("block carries {actual} transactions, the block capacity is {max}")]
+    TooManyTransactions { max: usize, actual: usize },
+    #[error("timestamp {actual} is earlier than the parent's {parent}")]
+    TimestampBeforeParent { parent: u64, actual: u64 },
+    #[error("timestamp {actual} is more than {max_skew_ms}ms ahead of the clock ({now})")]
+    TimestampInFuture { now: u64, actual: u64, max_skew_ms: u64 },
+    #[error(transparent)]
+    #[serde(untagged)]
+    Signature(#[from] SignatureError),
+    #[error(transparent)]
+    #[serde(untagged)]
+    Solution(#[from] SolutionError),
+}
+
+/// 제네시스 블록의 본문: 아무에게도 보상이 없는 코인베이스 하나
+pub fn genesis_body() -> BlockBody {
+    BlockBody::reward(GENESIS_NODE_ID, 0)
+}
+
+/// 제네시스 블록의 헤더 해시
+pub fn genesis_hash() -> [u8; 32] {
+    BlockHeader {
+        index: 0,
+        prev_hash: GENESIS_PREV_HASH,
+        timestamp: GENESIS_TIMESTAMP,
+        node_id: GENESIS_NODE_ID,
+        merkle_root: &genesis_body().merkle_root_hex(),
+        problem: &Problem::empty(0),
+        solution: &[],
+        difficulty: 0,
+        difficulty_target: 0,
+        nonce: 0,
+    }
+    .hash()
+}
+
+/// 체인 끝: 마지막으로 합의된 블록의 높이와 헤더 해시
+///
+/// 다음 블록의 문제는 이 값만으로 정해지므로, 같은 체인을 가진 노드는 누구나
+/// 서버가 낸 문제를 다시 만들어 볼 수 있다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTip {
+    pub height: u64,
+    pub hash: [u8; 32],
+}
+
+impl ChainTip {
+    pub fn genesis() -> Self {
+        ChainTip { height: 0, hash: genesis_hash() }
+    }
+
+    /// 다음 블록이 `prev_hash`에 적어야 할 값
+    pub fn hash_hex(&self) -> String {
+        hex::encode(self.hash)
+    }
+
+    /// 다음 블록의 높이
+    pub fn next_height(&self) -> u64 {
+        self.height + 1
+    }
+
+    /// 다음 블록의 문제를 만들 난수 생성기
+    pub fn rng(&self, domain: &str) -> ChaCha20Rng {
+        problem_rng(&self.hash, self.next_height(), domain)
+    }
+}
+
+/// 이전 블록 해시와 높이로 시드를 만든 ChaCha20 난수 생성기
+///
+/// 시드 = sha256(domain 길이, domain, 이전 블록 해시, 높이).
+/// `domain`으로 용도(퍼즐 종류 선택, 판 생성)를 나눠, 한쪽의 난수 사용이 다른 쪽에 영향을 주지 않게 한다.
+pub fn problem_rng(prev_hash: &[u8; 32], height: u64, domain: &str) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update((domain.len() as u64).to_be_bytes());
+    hasher.update(domain.as_bytes());
+    hasher.update(prev_hash);
+    hasher.update(height.to_be_bytes());
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+/// 서버가 문제를 내는 규칙 (서버 설정에서 정하고, 라운드에 담아 검증 노드에 알림)
+///
+/// 퍼즐 종류와 판 크기는 이 규칙, 체인 끝, 난이도만으로 정해지므로
+/// 검증하는 쪽은 블록의 문제가 스스로 밝힌 종류나 크기를 믿지 않고 문제를 다시 만들어 비교한다.
+/// 난이도는 서버의 풀이 시간 기록으로 정해지므로 따로 `check_difficulty`로 라운드와 비교한다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueRules {
+    pub kinds: Vec<PuzzleKind>,   // 고를 수 있는 퍼즐 종류 (체인 끝으로 시드를 정해 하나를 고름)
+    pub min_size: usize,          // 판 한 변의 최소 길이
+    pub max_size: usize,          // 난이도가 올라갈 때 키울 수 있는 최대 길이
+    pub unique: bool,             // 풀이가 하나뿐인 문제만 냄
+    pub work_target: Option<u32>, // 작업 증명 모드면 헤더 해시 앞자리 0 비트 수
+}
+
+impl Default for IssueRules {
+    /// 서버 기본 설정과 같은 규칙 (4x4 부터 8x8 까지의 마방진, 풀이 하나)
+    fn default() -> Self {
+        IssueRules { kinds: vec![PuzzleKind::MagicSquare], min_size: 4, max_size: 8, unique: true, work_target: None }
+    }
+}
+
+impl IssueRules {
+    /// 체인 끝 다음 높이에서 `difficulty`로 낼 문제
+    ///
+    /// 종류는 `KIND_DOMAIN` 난수로, 판은 `PUZZLE_DOMAIN` 난수로 만들어 서로 영향을 주지 않는다.
+    pub fn issue(&self, tip: &ChainTip, difficulty: u32) -> Problem {
+        if let Some(target_bits) = self.work_target {
+            return work_problem(target_bits);
+        }
+        // 종류가 비어 있는 규칙은 서버 설정이 막지만, 받은 라운드가 그렇다면 기본 종류로 만듦
+        let kind = self.kinds.choose(&mut tip.rng(KIND_DOMAIN)).copied().unwrap_or(PuzzleKind::MagicSquare);
+        let params = GenerateParams {
+            difficulty,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            unique: self.unique,
+        };
+        kind.puzzle().generate(&mut tip.rng(PUZZLE_DOMAIN), &params)
+    }
+
+    /// 문제가 이 규칙으로 체인 끝에서 낸 문제인지 (난이도는 문제에 적힌 값으로 다시 만듦)
+    pub fn check_problem(&self, problem: &Problem, tip: &ChainTip) -> Result<(), BlockError> {
+        if self.issue(tip, problem.board().difficulty) != *problem {
+            return Err(BlockError::ProblemNotIssued { height: tip.next_height() });
+        }
+        Ok(())
+    }
+}
+
+/// 지금 시각 (UTC 유닉스 시각, 밀리초), 블록의 `timestamp`에 적는 값
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// 블록 시각 규칙: 부모 블록보다 이르지 않고, 검사하는 쪽의 시계(`now`)보다 `max_skew_ms` 넘게 앞서지 않음
+pub fn check_timestamp(timestamp: u64, parent: u64, now: u64, max_skew_ms: u64) -> Result<(), BlockError> {
+    if timestamp < parent {
+        return Err(BlockError::TimestampBeforeParent { parent, actual: timestamp });
+    }
+    if timestamp > now.saturating_add(max_skew_ms) {
+        return Err(BlockError::TimestampInFuture { now, actual: timestamp, max_skew_ms });
+    }
+    Ok(())
+}
+
+/// 블록 난이도 규칙: 헤더의 난이도와 문제 판의 난이도가 모두 문제를 낸 쪽이 정한 난이도(`expected`)
+pub fn check_difficulty(difficulty: u32, problem: &Problem, expected: u32) -> Result<(), BlockError> {
+    for actual in [difficulty, problem.board().difficulty] {
+        if actual != expected {
+            return Err(BlockError::WrongDifficulty { expected, actual });
+        }
+    }
+    Ok(())
+}
+
+/// 블록 용량 규칙: 코인베이스를 뺀 거래가 `max_transactions`개 이하
+pub fn check_capacity(body: &BlockBody, max_transactions: usize) -> Result<(), BlockError> {
+    let actual = body.transactions.len();
+    if actual > max_transactions {
+        return Err(BlockError::TooManyTransactions { max: max_transactions, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Transaction;
+    use crate::puzzles::PuzzleKind;
+    use proptest::prelude::*;
+
+    #[test]
+    fn timestamps_follow_the_parent_within_the_skew() {
+        let parent = GENESIS_TIMESTAMP;
+        let now = parent + 10_000;
+        assert_eq!(check_timestamp(parent, parent, now, 1_000), Ok(()));
+        assert_eq!(check_timestamp(now + 1_000, parent, now, 1_000), Ok(()));
+        assert_eq!(
+            check_timestamp(parent - 1, parent, now, 1_000),
+            Err(BlockError::TimestampBeforeParent { parent, actual: parent - 1 })
+        );
+        assert_eq!(
+            check_timestamp(now + 1_001, parent, now, 1_000),
+            Err(BlockError::TimestampInFuture { now, actual: now + 1_001, max_skew_ms: 1_000 })
+        );
+    }
+
+    #[test]
+    fn difficulty_must_match_the_issued_difficulty() {
+        let problem = work_problem(12);
+        assert_eq!(check_difficulty(12, &problem, 12), Ok(()));
+        assert_eq!(check_difficulty(1, &problem, 12), Err(BlockError::WrongDifficulty { expected: 12, actual: 1 }));
+        assert_eq!(check_difficulty(12, &work_problem(1), 12), Err(BlockError::WrongDifficulty { expected: 12, actual: 1 }));
+    }
+
+    #[test]
+    fn blocks_over_capacity_are_rejected() {
+        let body = BlockBody {
+            transactions: (1..=3).map(|amount| Transaction::new("a", "b", amount)).collect(),
+            ..BlockBody::reward("miner", 10)
+        };
+        assert_eq!(check_capacity(&body, 3), Ok(()));
+        assert_eq!(check_capacity(&body, 2), Err(BlockError::TooManyTransactions { max: 2, actual: 3 }));
+        assert_eq!(check_capacity(&BlockBody::reward("miner", 10), 0), Ok(()));
+    }
+
+    fn tip(seed: u64) -> ChainTip {
+        ChainTip { height: seed % 1000, hash: Sha256::digest(seed.to_be_bytes()).into() }
+    }
+
+    fn rules(kinds: Vec<PuzzleKind>) -> IssueRules {
+        IssueRules { kinds, ..IssueRules::default() }
+    }
+
+    #[test]
+    fn problems_of_another_kind_or_size_are_not_issued() {
+        let tip = tip(7);
+        let sudoku = rules(vec![PuzzleKind::Sudoku]);
+        let issued = sudoku.issue(&tip, 6);
+        assert_eq!(sudoku.check_problem(&issued, &tip), Ok(()));
+
+        // 같은 시드, 같은 난이도라도 규칙에 없는 종류나 크기로 바꾼 문제는 거절
+        let latin = rules(vec![PuzzleKind::LatinSquare]).issue(&tip, 6);
+        assert_eq!(sudoku.check_problem(&latin, &tip), Err(BlockError::ProblemNotIssued { height: tip.next_height() }));
+        let larger = IssueRules { min_size: 6, ..sudoku.clone() }.issue(&tip, 6);
+        assert_eq!(sudoku.check_problem(&larger, &tip), Err(BlockError::ProblemNotIssued { height: tip.next_height() }));
+    }
+
+    #[test]
+    fn work_target_comes_from_the_rules() {
+        let tip = tip(7);
+        let pow = IssueRules { work_target: Some(16), ..IssueRules::default() };
+        assert_eq!(pow.check_problem(&work_problem(16), &tip), Ok(()));
+        assert!(pow.check_problem(&work_problem(0), &tip).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn issued_problems_can_be_regenerated(
+            seed in any::<u64>(),
+            difficulty in 1u32..=20,
+            kinds in prop::sample::subsequence(vec![
+                PuzzleKind::MagicSquare,
+                PuzzleKind::LatinSquare,
+                PuzzleKind::Sudoku,
+                PuzzleKind::KenKen,
+            ], 1..=4),
+            unique in any::<bool>(),
+        ) {
+            let tip = tip(seed);
+            let rules = IssueRules { unique, ..rules(kinds) };
+            let problem = rules.issue(&tip, difficulty);
+            prop_assert_eq!(rules.check_problem(&problem, &tip), Ok(()));
+        }
+
+        #[test]
+        fn problems_do_not_carry_over_to_another_tip(seed in any::<u64>(), difficulty in 4u32..=12) {
+            let tip = tip(seed);
+            let rules = rules(vec![PuzzleKind::LatinSquare]);
+            let problem = rules.issue(&tip, difficulty);
+            let next = ChainTip { height: tip.next_height(), hash: tip.hash };
+            prop_assert!(rules.check_problem(&problem, &next).is_err());
+        }
+    }
+}
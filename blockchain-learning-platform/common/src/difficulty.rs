@@ -0,0 +1,61 @@
+// common/src/difficulty.rs
+
+/// 빈 칸이 전체 칸의 이 비율을 넘으면 판 크기를 키움
+const MAX_BLANK_RATIO: (usize, usize) = (3, 4);
+
+/// 난이도(빈 칸 수)를 실제 문제 모양 (크기, 빈 칸 수)로 변환
+///
+/// `min_size..=max_size` 중 퍼즐이 허용하는(`allowed`) 크기에서
+/// 빈 칸이 전체의 3/4 를 넘지 않는 가장 작은 크기를 고르고,
+/// 가장 큰 크기에서도 넘치면 그 크기에서 비울 수 있는 만큼만 비운다.
+/// 범위 안에 허용되는 크기가 없으면 `min_size` 이상에서 처음 허용되는 크기를 쓴다.
+pub fn layout(
+    difficulty: u32,
+    min_size: usize,
+    max_size: usize,
+    allowed: impl Fn(usize) -> bool,
+) -> (usize, usize) {
+    let blanks = difficulty as usize;
+    let max_blanks = |n: usize| n * n * MAX_BLANK_RATIO.0 / MAX_BLANK_RATIO.1;
+
+    let sizes: Vec<usize> = (min_size..=max_size.max(min_size)).filter(|&n| allowed(n)).collect();
+    let size = sizes
+        .iter()
+        .copied()
+        .find(|&n| blanks <= max_blanks(n))
+        .or_else(|| sizes.last().copied())
+        .unwrap_or_else(|| (min_size..).find(|&n| allowed(n)).expect("puzzle allows some size"));
+    (size, blanks.min(max_blanks(size)).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_grows_once_blanks_pass_three_quarters() {
+        // 4x4 는 빈 칸 12 개까지, 5x5 는 18 개까지
+        assert_eq!(layout(12, 4, 8, |_| true), (4, 12));
+        assert_eq!(layout(13, 4, 8, |_| true), (5, 13));
+        assert_eq!(layout(19, 4, 8, |_| true), (6, 19));
+    }
+
+    #[test]
+    fn blanks_are_capped_at_the_largest_size() {
+        assert_eq!(layout(100, 4, 5, |_| true), (5, 18));
+    }
+
+    #[test]
+    fn at_least_one_blank() {
+        assert_eq!(layout(0, 4, 8, |_| true), (4, 1));
+    }
+
+    #[test]
+    fn only_allowed_sizes_are_used() {
+        // 스도쿠처럼 4, 6, 8 만 허용: 13 칸은 5x5 대신 6x6
+        let even = |n: usize| n.is_multiple_of(2);
+        assert_eq!(layout(13, 4, 8, even), (6, 13));
+        // 범위 안에 허용되는 크기가 없으면 범위 밖에서 처음 허용되는 크기
+        assert_eq!(layout(3, 5, 5, even), (6, 3));
+    }
+}
@@ -0,0 +1,215 @@
+// common/src/emission.rs
+
+//! 블록 보상(코인베이스)의 발행 규칙
+//!
+//! 보상은 블록 높이만으로 정해지므로, 원장 없이 블록 하나만 보고 코인베이스 금액을 검사할 수 있고
+//! 체인 끝 높이만 알면 지금까지의 총 발행량도 계산할 수 있다.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::body::BLOCK_REWARD;
+use crate::chain::BlockError;
+use crate::protocol::Block;
+
+/// 발행 규칙 (서버 설정 `EMISSION`, 라운드에 담아 클라이언트에 알림)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "schedule", rename_all = "snake_case")]
+pub enum Emission {
+    /// 블록마다 같은 보상
+    Fixed { reward: u64 },
+    /// 처음 보상 `initial`을 `interval` 블록마다 반으로 (비트코인)
+    Halving { initial: u64, interval: u64 },
+    /// 블록마다 `reward`, 총 발행량이 `cap`에 이르면 멈춤 (마지막 블록은 남은 만큼)
+    Capped { reward: u64, cap: u64 },
+}
+
+impl Default for Emission {
+    /// 블록마다 `BLOCK_REWARD`
+    fn default() -> Self {
+        Emission::Fixed { reward: BLOCK_REWARD }
+    }
+}
+
+impl Emission {
+    /// `height` 블록의 코인베이스 금액 (제네시스는 0)
+    pub fn reward(&self, height: u64) -> u64 {
+        if height == 0 {
+            return 0;
+        }
+        match *self {
+            Emission::Fixed { reward } => reward,
+            Emission::Halving { initial, interval } => {
+                let halvings = (height - 1) / interval.max(1);
+                initial.checked_shr(u32::try_from(halvings).unwrap_or(u32::MAX)).unwrap_or(0)
+            }
+            Emission::Capped { reward, cap } => reward.min(cap.saturating_sub(reward.saturating_mul(height - 1))),
+        }
+    }
+
+    /// 제네시스부터 `height` 블록까지 발행된 코인 수
+    pub fn supply(&self, height: u64) -> u64 {
+        match *self {
+            Emission::Fixed { reward } => reward.saturating_mul(height),
+            Emission::Capped { reward, cap } => reward.saturating_mul(height).min(cap),
+            Emission::Halving { initial, interval } => {
+                let interval = interval.max(1);
+                let (mut supply, mut start, mut reward) = (0u64, 1u64, initial);
+                // 반감기 한 번 동안(`start`부터 `interval` 블록)은 같은 보상
+                while reward > 0 && start <= height {
+                    let blocks = interval.min(height - start + 1);
+                    supply = supply.saturating_add(reward.saturating_mul(blocks));
+                    start = start.saturating_add(interval);
+                    reward >>= 1;
+                }
+                supply
+            }
+        }
+    }
+
+    /// 체인 끝이 `height`일 때 다음으로 보상이 반으로 줄어드는 블록 높이
+    /// 반감 규칙이 아니거나 보상이 이미 0 이면 `None`
+    pub fn next_halving(&self, height: u64) -> Option<u64> {
+        let Emission::Halving { interval, .. } = *self else {
+            return None;
+        };
+        // 반감은 interval + 1, 2 * interval + 1, ... 높이에서 일어남
+        let interval = interval.max(1);
+        let next = height.div_ceil(interval).max(1).checked_mul(interval)?.checked_add(1)?;
+        (self.reward(next - 1) > 0).then_some(next)
+    }
+
+    /// 코인베이스가 블록을 낸 노드에게 그 높이의 보상과 블록에 담긴 거래 수수료만큼 주는지 검사
+    pub fn check_coinbase(&self, block: &Block) -> Result<(), BlockError> {
+        let coinbase = &block.body.coinbase;
+        if coinbase.receiver_id != block.node_id {
+            return Err(BlockError::CoinbaseNotToMiner {
+                miner: block.node_id.clone(),
+                receiver: coinbase.receiver_id.clone(),
+            });
+        }
+        let expected = self.reward(block.index).saturating_add(block.body.fees());
+        if coinbase.amount != expected {
+            return Err(BlockError::WrongReward { height: block.index, expected, actual: coinbase.amount });
+        }
+        Ok(())
+    }
+}
+
+/// `fixed:<보상>`, `halving:<처음 보상>:<간격>`, `capped:<보상>:<총 발행 한도>`
+impl FromStr for Emission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowered = s.trim().to_ascii_lowercase();
+        let mut parts = lowered.split(':');
+        let schedule = parts.next().unwrap_or_default();
+        let numbers = parts
+            .map(|part| part.trim().parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid emission schedule {:?}: {}", s, e))?;
+        match (schedule, numbers.as_slice()) {
+            ("fixed", []) => Ok(Emission::default()),
+            ("fixed", &[reward]) => Ok(Emission::Fixed { reward }),
+            ("halving", &[_, 0]) => Err("halving interval must be positive".to_string()),
+            ("halving", &[initial, interval]) => Ok(Emission::Halving { initial, interval }),
+            ("capped", &[reward, cap]) => Ok(Emission::Capped { reward, cap }),
+            _ => Err(format!(
+                "unknown emission schedule {:?} (expected fixed:<reward>, halving:<initial>:<interval> or capped:<reward>:<cap>)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Emission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Emission::Fixed { reward } => write!(f, "fixed:{}", reward),
+            Emission::Halving { initial, interval } => write!(f, "halving:{}:{}", initial, interval),
+            Emission::Capped { reward, cap } => write!(f, "capped:{}:{}", reward, cap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::{BlockBody, Transaction};
+    use crate::test_support::block_with_body;
+
+    #[test]
+    fn halving_rewards_and_supply() {
+        let emission = Emission::Halving { initial: 50, interval: 3 };
+        let rewards: Vec<u64> = (0..=12).map(|height| emission.reward(height)).collect();
+        assert_eq!(rewards, [0, 50, 50, 50, 25, 25, 25, 12, 12, 12, 6, 6, 6]);
+        for height in 0..=40 {
+            let summed: u64 = (1..=height).map(|h| emission.reward(h)).sum();
+            assert_eq!(emission.supply(height), summed, "height {}", height);
+        }
+        // 50 + 25 + 12 + 6 + 3 + 1 = 97 블록 3개씩
+        assert_eq!(emission.supply(u64::MAX), 97 * 3);
+
+        assert_eq!(emission.next_halving(0), Some(4));
+        assert_eq!(emission.next_halving(3), Some(4));
+        assert_eq!(emission.next_halving(4), Some(7));
+        // 1 -> 0 이 마지막 반감, 그 뒤로는 없음
+        assert_eq!(emission.next_halving(16), Some(19));
+        assert_eq!(emission.next_halving(19), None);
+        assert_eq!(Emission::default().next_halving(5), None);
+    }
+
+    #[test]
+    fn capped_supply_stops_at_the_cap() {
+        let emission = Emission::Capped { reward: 10, cap: 25 };
+        let rewards: Vec<u64> = (0..=4).map(|height| emission.reward(height)).collect();
+        assert_eq!(rewards, [0, 10, 10, 5, 0]);
+        assert_eq!(emission.supply(2), 20);
+        assert_eq!(emission.supply(3), 25);
+        assert_eq!(emission.supply(1000), 25);
+        assert_eq!(Emission::default().supply(3), 3 * BLOCK_REWARD);
+    }
+
+    #[test]
+    fn coinbase_must_pay_the_miner_the_scheduled_reward() {
+        let emission = Emission::Halving { initial: 8, interval: 2 };
+        let block = |index: u64, receiver: &str, amount: u64| block_with_body(index, "miner", BlockBody::reward(receiver, amount));
+        assert_eq!(emission.check_coinbase(&block(3, "miner", 4)), Ok(()));
+        assert_eq!(
+            emission.check_coinbase(&block(3, "miner", 8)),
+            Err(BlockError::WrongReward { height: 3, expected: 4, actual: 8 })
+        );
+
+        // 거래 수수료도 블록을 낸 노드가 받음
+        let mut with_fees = block(3, "miner", 4);
+        with_fees.body.transactions = vec![Transaction { fee: 2, ..Transaction::new("a", "b", 1) }];
+        assert_eq!(
+            emission.check_coinbase(&with_fees),
+            Err(BlockError::WrongReward { height: 3, expected: 6, actual: 4 })
+        );
+        with_fees.body.coinbase.amount = 6;
+        assert_eq!(emission.check_coinbase(&with_fees), Ok(()));
+
+        assert_eq!(
+            emission.check_coinbase(&block(3, "thief", 4)),
+            Err(BlockError::CoinbaseNotToMiner { miner: "miner".to_string(), receiver: "thief".to_string() })
+        );
+    }
+
+    #[test]
+    fn schedules_parse_from_their_display_form() {
+        for emission in [
+            Emission::default(),
+            Emission::Halving { initial: 50, interval: 210 },
+            Emission::Capped { reward: 10, cap: 1000 },
+        ] {
+            assert_eq!(emission.to_string().parse(), Ok(emission));
+        }
+        assert_eq!("fixed".parse(), Ok(Emission::default()));
+        assert!("halving:50:0".parse::<Emission>().is_err());
+        assert!("halving:50".parse::<Emission>().is_err());
+        assert!("linear:1".parse::<Emission>().is_err());
+    }
+}
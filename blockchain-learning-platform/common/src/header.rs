@@ -0,0 +1,173 @@
+// common/src/header.rs
+
+use sha2::{Digest, Sha256};
+
+use crate::problem::{Board, Problem};
+
+/// 블록 해시에 들어가는 값들 (서버와 클라이언트의 `Block`이 같은 규칙으로 해시를 계산)
+///
+/// 인코딩: 정수는 빅엔디언 (`timestamp`는 UTC 유닉스 시각 밀리초, u64), 문자열은 길이(u64) 뒤에 UTF-8 바이트,
+/// 목록은 길이(u64) 뒤에 각 항목, `Option`은 0(없음) 또는 1 뒤에 값.
+/// 순서: index, prev_hash, timestamp, node_id, merkle_root, problem, solution,
+/// difficulty, difficulty_target, nonce
+#[derive(Debug, Clone, Copy)]
+pub struct BlockHeader<'a> {
+    pub index: u64,
+    pub prev_hash: &'a str, // 부모 블록 해시 (hex)
+    pub timestamp: u64,
+    pub node_id: &'a str,
+    pub merkle_root: &'a str, // 블록 본문(코인베이스와 거래)의 머클 루트 (hex)
+    pub problem: &'a Problem,
+    pub solution: &'a [Vec<u32>],
+    pub difficulty: u32,
+    pub difficulty_target: u32,
+    pub nonce: u64,
+}
+
+impl BlockHeader<'_> {
+    /// 헤더의 sha256 해시 (블록 해시, 작업 증명 목표, 다음 문제의 시드)
+    pub fn hash(&self) -> [u8; 32] {
+        let mut encoder = Encoder::new();
+        encoder.u64(self.index);
+        encoder.str(self.prev_hash);
+        encoder.u64(self.timestamp);
+        encoder.str(self.node_id);
+        encoder.str(self.merkle_root);
+        encoder.problem(self.problem);
+        encoder.matrix(self.solution);
+        encoder.u32(self.difficulty);
+        encoder.u32(self.difficulty_target);
+        encoder.u64(self.nonce);
+        encoder.finish()
+    }
+}
+
+/// 값을 정해진 바이트 순서로 해시에 넣음 (블록 헤더, 머클 트리의 잎)
+pub(crate) struct Encoder(Sha256);
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Encoder(Sha256::new())
+    }
+
+    /// 머클 트리의 잎: 0x00 뒤에 잎 종류 이름
+    pub(crate) fn leaf(kind: &str) -> Self {
+        let mut encoder = Encoder::new();
+        encoder.0.update([0]);
+        encoder.str(kind);
+        encoder
+    }
+
+    pub(crate) fn finish(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.0.update(value.to_be_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.0.update(value.to_be_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u64(len as u64);
+    }
+
+    pub(crate) fn str(&mut self, value: &str) {
+        self.len(value.len());
+        self.0.update(value.as_bytes());
+    }
+
+    fn matrix(&mut self, matrix: &[Vec<u32>]) {
+        self.len(matrix.len());
+        for row in matrix {
+            self.len(row.len());
+            for &value in row {
+                self.u32(value);
+            }
+        }
+    }
+
+    fn board(&mut self, board: &Board) {
+        self.len(board.size);
+        self.matrix(&board.matrix);
+        self.u32(board.difficulty);
+        match board.solution_count {
+            Some(count) => {
+                self.0.update([1]);
+                self.u32(count);
+            }
+            None => self.0.update([0]),
+        }
+    }
+
+    fn problem(&mut self, problem: &Problem) {
+        // 종류 이름은 JSON 태그와 같음
+        let kind = match problem {
+            Problem::MagicSquare(_) => "magic_square",
+            Problem::LatinSquare(_) => "latin_square",
+            Problem::Sudoku(_) => "sudoku",
+            Problem::KenKen { .. } => "kenken",
+            Problem::ProofOfWork(_) => "proof_of_work",
+        };
+        self.str(kind);
+        self.board(problem.board());
+        if let Problem::KenKen { cages, .. } = problem {
+            self.len(cages.len());
+            for cage in cages {
+                self.len(cage.cells.len());
+                for &(row, col) in &cage.cells {
+                    self.len(row);
+                    self.len(col);
+                }
+                self.str(cage.op.symbol());
+                self.u32(cage.target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{GENESIS_NODE_ID, GENESIS_PREV_HASH, GENESIS_TIMESTAMP};
+
+    #[test]
+    fn every_field_changes_the_hash() {
+        let problem = Problem::empty(2);
+        let solution = vec![vec![1, 2], vec![2, 1]];
+        let header = BlockHeader {
+            index: 1,
+            prev_hash: GENESIS_PREV_HASH,
+            timestamp: GENESIS_TIMESTAMP,
+            node_id: GENESIS_NODE_ID,
+            merkle_root: GENESIS_PREV_HASH,
+            problem: &problem,
+            solution: &solution,
+            difficulty: 4,
+            difficulty_target: 0,
+            nonce: 0,
+        };
+        let hash = header.hash();
+        assert_eq!(hash, header.hash());
+
+        let other_problem = Problem::empty(3);
+        let other_solution = vec![vec![2, 1], vec![1, 2]];
+        let tampered = [
+            BlockHeader { index: 2, ..header },
+            BlockHeader { prev_hash: "00", ..header },
+            BlockHeader { timestamp: GENESIS_TIMESTAMP + 1, ..header },
+            BlockHeader { node_id: "someone", ..header },
+            BlockHeader { merkle_root: "1000", ..header },
+            BlockHeader { problem: &other_problem, ..header },
+            BlockHeader { solution: &other_solution, ..header },
+            BlockHeader { difficulty: 5, ..header },
+            BlockHeader { difficulty_target: 1, ..header },
+            BlockHeader { nonce: 1, ..header },
+        ];
+        for other in tampered {
+            assert_ne!(other.hash(), hash, "{:?}", other);
+        }
+    }
+}
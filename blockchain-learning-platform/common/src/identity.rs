@@ -0,0 +1,226 @@
+// common/src/identity.rs
+
+use ed25519_dalek::{Signature, Signer, VerifyingKey};
+use rand::{CryptoRng, RngCore};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+pub use ed25519_dalek::SigningKey;
+
+use crate::body::{BlockBody, Transaction, TransactionError};
+use crate::chain::BlockError;
+use crate::header::BlockHeader;
+
+/// 블록이나 투표의 서명을 확인할 수 없는 이유
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum SignatureError {
+    #[error("node id {node_id:?} is not a hex-encoded Ed25519 public key")]
+    InvalidNodeId { node_id: String },
+    #[error("signature does not match the node id")]
+    BadSignature,
+}
+
+/// 새 노드 키 생성
+pub fn generate_key<R: RngCore + CryptoRng>(rng: &mut R) -> SigningKey {
+    SigningKey::generate(rng)
+}
+
+/// 노드 아이디 = 공개 키(32바이트)의 hex
+///
+/// 아이디만 보고 누구나 서명을 확인할 수 있으므로, 다른 노드의 아이디로 블록을 낼 수 없다.
+pub fn node_id(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+/// 블록 해시에 서명하고 서명(64바이트)을 hex 로 돌려줌
+pub fn sign_block(key: &SigningKey, header: &BlockHeader) -> String {
+    hex::encode(key.sign(&header.hash()).to_bytes())
+}
+
+/// 블록 서명이 `node_id`의 키로 블록 해시에 한 서명인지 검사
+pub fn verify_block(header: &BlockHeader, signature: &str) -> Result<(), SignatureError> {
+    let public_key = parse_node_id(header.node_id)?;
+    let signature = parse_signature(signature)?;
+    public_key
+        .verify_strict(&header.hash(), &signature)
+        .map_err(|_| SignatureError::BadSignature)
+}
+
+/// 검증 투표에 서명하고 서명을 hex 로 돌려줌
+pub fn sign_vote(key: &SigningKey, round_id: u64, block_hash: &str, is_valid: bool) -> String {
+    hex::encode(key.sign(&vote_digest(round_id, block_hash, is_valid)).to_bytes())
+}
+
+/// 투표 서명이 `node_id`의 키로 (라운드, 블록 해시, 찬반)에 한 서명인지 검사
+pub fn verify_vote(
+    node_id: &str,
+    round_id: u64,
+    block_hash: &str,
+    is_valid: bool,
+    signature: &str,
+) -> Result<(), SignatureError> {
+    let public_key = parse_node_id(node_id)?;
+    let signature = parse_signature(signature)?;
+    public_key
+        .verify_strict(&vote_digest(round_id, block_hash, is_valid), &signature)
+        .map_err(|_| SignatureError::BadSignature)
+}
+
+/// 거래에 보내는 쪽의 키로 서명하고 서명을 hex 로 돌려줌 (`transaction.signature`에 넣음)
+pub fn sign_transaction(key: &SigningKey, transaction: &Transaction) -> String {
+    hex::encode(key.sign(&transaction_digest(transaction)).to_bytes())
+}
+
+/// 거래 서명이 `sender_id`의 키로 거래에 한 서명인지 검사
+/// 보내는 쪽 아이디가 공개 키가 아니면 누구도 서명할 수 없으므로 역시 거절
+pub fn verify_transaction(transaction: &Transaction) -> Result<(), TransactionError> {
+    let bad_signature = || TransactionError::BadSignature { account: transaction.sender_id.clone() };
+    let public_key = parse_node_id(&transaction.sender_id).map_err(|_| bad_signature())?;
+    let signature = parse_signature(&transaction.signature).map_err(|_| bad_signature())?;
+    public_key
+        .verify_strict(&transaction_digest(transaction), &signature)
+        .map_err(|_| bad_signature())
+}
+
+/// 블록 본문의 거래가 모두 보내는 쪽의 서명을 가졌는지 검사 (코인베이스는 보내는 쪽이 없어 서명도 없음)
+pub fn verify_transactions(body: &BlockBody) -> Result<(), BlockError> {
+    body.transactions.iter().enumerate().try_for_each(|(index, transaction)| {
+        verify_transaction(transaction).map_err(|error| BlockError::InvalidTransaction { index, error })
+    })
+}
+
+/// 거래 서명 대상 = sha256("transaction", 거래 해시)
+/// 앞의 "transaction" 덕분에 블록이나 투표 서명을 거래 서명으로 다시 쓸 수 없음
+fn transaction_digest(transaction: &Transaction) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"transaction");
+    hasher.update(transaction.hash());
+    hasher.finalize().into()
+}
+
+/// 투표 서명 대상 = sha256("vote", round_id, 블록 해시 길이, 블록 해시, 찬반)
+/// 앞의 "vote" 덕분에 블록 서명을 투표 서명으로 다시 쓸 수 없음
+fn vote_digest(round_id: u64, block_hash: &str, is_valid: bool) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"vote");
+    hasher.update(round_id.to_be_bytes());
+    hasher.update((block_hash.len() as u64).to_be_bytes());
+    hasher.update(block_hash.as_bytes());
+    hasher.update([is_valid as u8]);
+    hasher.finalize().into()
+}
+
+fn parse_signature(signature: &str) -> Result<Signature, SignatureError> {
+    let bytes: [u8; 64] = hex::decode(signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SignatureError::BadSignature)?;
+    Ok(Signature::from_bytes(&bytes))
+}
+
+/// hex 노드 아이디를 공개 키로 되돌림
+pub fn parse_node_id(node_id: &str) -> Result<VerifyingKey, SignatureError> {
+    let invalid = || SignatureError::InvalidNodeId { node_id: node_id.to_string() };
+    let bytes: [u8; 32] = hex::decode(node_id)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{GENESIS_PREV_HASH, GENESIS_TIMESTAMP};
+    use crate::problem::Problem;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn only_the_node_key_can_sign_its_blocks() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let key = generate_key(&mut rng);
+        let other = generate_key(&mut rng);
+        let node_id = node_id(&key);
+        let problem = Problem::empty(2);
+        let header = BlockHeader {
+            index: 1,
+            prev_hash: GENESIS_PREV_HASH,
+            timestamp: GENESIS_TIMESTAMP,
+            node_id: &node_id,
+            merkle_root: GENESIS_PREV_HASH,
+            problem: &problem,
+            solution: &[],
+            difficulty: 0,
+            difficulty_target: 0,
+            nonce: 0,
+        };
+
+        let signature = sign_block(&key, &header);
+        assert_eq!(verify_block(&header, &signature), Ok(()));
+
+        // 내용을 바꾸거나 다른 키로 서명하면 거절
+        let tampered = BlockHeader { merkle_root: "1000", ..header };
+        assert_eq!(verify_block(&tampered, &signature), Err(SignatureError::BadSignature));
+        assert_eq!(verify_block(&header, &sign_block(&other, &header)), Err(SignatureError::BadSignature));
+        assert_eq!(verify_block(&header, "00"), Err(SignatureError::BadSignature));
+
+        let anonymous = BlockHeader { node_id: "JunhoKim123", ..header };
+        assert_eq!(
+            verify_block(&anonymous, &signature),
+            Err(SignatureError::InvalidNodeId { node_id: "JunhoKim123".to_string() })
+        );
+    }
+
+    #[test]
+    fn votes_are_bound_to_their_block_and_verdict() {
+        let mut rng = ChaCha20Rng::seed_from_u64(8);
+        let key = generate_key(&mut rng);
+        let voter = node_id(&key);
+        let signature = sign_vote(&key, 3, "abcd", true);
+
+        assert_eq!(verify_vote(&voter, 3, "abcd", true, &signature), Ok(()));
+        assert_eq!(verify_vote(&voter, 3, "abcd", false, &signature), Err(SignatureError::BadSignature));
+        assert_eq!(verify_vote(&voter, 3, "abce", true, &signature), Err(SignatureError::BadSignature));
+        assert_eq!(verify_vote(&voter, 4, "abcd", true, &signature), Err(SignatureError::BadSignature));
+        assert_eq!(verify_vote(&voter, 3, "abcd", true, ""), Err(SignatureError::BadSignature));
+    }
+
+    #[test]
+    fn only_the_sender_can_sign_its_transactions() {
+        let mut rng = ChaCha20Rng::seed_from_u64(9);
+        let key = generate_key(&mut rng);
+        let other = generate_key(&mut rng);
+        let sender = node_id(&key);
+        let mut transaction = Transaction { nonce: 2, ..Transaction::new(sender.clone(), node_id(&other), 3) };
+        let unsigned = transaction.hash();
+        transaction.signature = sign_transaction(&key, &transaction);
+        assert_eq!(verify_transaction(&transaction), Ok(()));
+        // 서명은 해시에 들어가지 않음
+        assert_eq!(transaction.hash(), unsigned);
+
+        // 내용을 바꾸거나, 다른 키로 서명하거나, 서명이 없으면 거절
+        let bad_signature = Err(TransactionError::BadSignature { account: sender.clone() });
+        assert_eq!(verify_transaction(&Transaction { amount: 4, ..transaction.clone() }), bad_signature);
+        assert_eq!(verify_transaction(&Transaction { nonce: 3, ..transaction.clone() }), bad_signature);
+        let forged = Transaction { signature: sign_transaction(&other, &transaction), ..transaction.clone() };
+        assert_eq!(verify_transaction(&forged), bad_signature);
+        assert_eq!(verify_transaction(&Transaction { signature: String::new(), ..transaction.clone() }), bad_signature);
+        assert_eq!(
+            verify_transaction(&Transaction::new("JunhoKim123", sender.clone(), 1)),
+            Err(TransactionError::BadSignature { account: "JunhoKim123".to_string() })
+        );
+
+        // 블록 본문에서는 몇 번째 거래가 틀렸는지 알려줌
+        let mut body = BlockBody::reward(sender.clone(), 10);
+        body.transactions = vec![transaction.clone(), forged];
+        assert_eq!(
+            verify_transactions(&body),
+            Err(BlockError::InvalidTransaction { index: 1, error: TransactionError::BadSignature { account: sender } })
+        );
+        body.transactions.pop();
+        assert_eq!(verify_transactions(&body), Ok(()));
+    }
+}
@@ -0,0 +1,136 @@
+// common/src/ledger.rs
+
+//! 체인마다 고르는 원장 모델: 이더리움처럼 계정마다 잔액을 두는 방식과 비트코인처럼 쓰지 않은 출력(UTXO)을 두는 방식
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::AccountState;
+use crate::body::{Transaction, TransactionError};
+use crate::chain::BlockError;
+use crate::protocol::Block;
+use crate::utxo::UtxoSet;
+
+/// 원장 모델 (서버 설정 `LEDGER_MODEL`, 라운드에 담아 클라이언트에 알림)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerModel {
+    /// 계정마다 잔액 (기본)
+    #[default]
+    Account,
+    /// 거래가 이전 출력을 쓰고 새 출력을 만듦
+    Utxo,
+}
+
+impl FromStr for LedgerModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "account" | "accounts" => Ok(LedgerModel::Account),
+            "utxo" => Ok(LedgerModel::Utxo),
+            other => Err(format!("unknown ledger model: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for LedgerModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LedgerModel::Account => "account",
+            LedgerModel::Utxo => "utxo",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 체인을 재생한 원장 (모델에 따라 계정 잔액 또는 UTXO 집합)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ledger {
+    Account(AccountState),
+    Utxo(UtxoSet),
+}
+
+impl Ledger {
+    /// 아무 블록도 반영하지 않은 `chain_id` 체인의 원장
+    pub fn new(model: LedgerModel, chain_id: u32) -> Self {
+        match model {
+            LedgerModel::Account => Ledger::Account(AccountState::new(chain_id)),
+            LedgerModel::Utxo => Ledger::Utxo(UtxoSet::new(chain_id)),
+        }
+    }
+
+    /// 블록들을 높이 순서대로 반영 (제네시스는 건너뜀)
+    pub fn replay<'a>(
+        model: LedgerModel,
+        chain_id: u32,
+        blocks: impl IntoIterator<Item = &'a Block>,
+    ) -> Result<Self, (u64, BlockError)> {
+        let mut ledger = Ledger::new(model, chain_id);
+        for block in blocks.into_iter().filter(|block| block.index > 0) {
+            ledger.apply_block(block).map_err(|e| (block.index, e))?;
+        }
+        Ok(ledger)
+    }
+
+    pub fn model(&self) -> LedgerModel {
+        match self {
+            Ledger::Account(_) => LedgerModel::Account,
+            Ledger::Utxo(_) => LedgerModel::Utxo,
+        }
+    }
+
+    pub fn chain_id(&self) -> u32 {
+        match self {
+            Ledger::Account(accounts) => accounts.chain_id,
+            Ledger::Utxo(utxos) => utxos.chain_id,
+        }
+    }
+
+    /// `account`가 다음에 보낼 거래의 nonce (UTXO 원장은 nonce 를 쓰지 않으므로 0)
+    pub fn next_nonce(&self, account: &str) -> u64 {
+        match self {
+            Ledger::Account(accounts) => accounts.next_nonce(account),
+            Ledger::Utxo(_) => 0,
+        }
+    }
+
+    /// 마지막으로 반영한 블록 높이
+    pub fn height(&self) -> u64 {
+        match self {
+            Ledger::Account(accounts) => accounts.height,
+            Ledger::Utxo(utxos) => utxos.height,
+        }
+    }
+
+    /// 계정 잔액, UTXO 원장에서는 그 계정이 가진 쓰지 않은 출력의 합
+    pub fn balance(&self, account: &str) -> u64 {
+        match self {
+            Ledger::Account(accounts) => accounts.balance(account),
+            Ledger::Utxo(utxos) => utxos.balance(account),
+        }
+    }
+
+    /// 체인 끝 다음 블록을 반영 (실패하면 아무것도 바꾸지 않음)
+    pub fn apply_block(&mut self, block: &Block) -> Result<(), BlockError> {
+        match self {
+            Ledger::Account(accounts) => accounts.apply_block(block),
+            Ledger::Utxo(utxos) => utxos.apply_block(block).map(|_| ()),
+        }
+    }
+
+    /// 블록을 반영할 수 있는지만 확인
+    pub fn check_block(&self, block: &Block) -> Result<(), BlockError> {
+        self.clone().apply_block(block)
+    }
+
+    /// 거래 하나를 반영
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> Result<(), TransactionError> {
+        match self {
+            Ledger::Account(accounts) => accounts.apply_transaction(transaction),
+            Ledger::Utxo(utxos) => utxos.apply_transaction(transaction).map(|_| ()),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// common/src/lib.rs
+
+//! 서버와 클라이언트가 함께 쓰는 코드
+//!
+//! 주고받는 메시지와 저장하는 블록 타입, 블록 본문(거래)과 머클 루트, 블록 보상 발행 규칙, 체인을 재생한 원장(계정 잔액 또는 UTXO), 문제 모양, 퍼즐 생성/검증, 블록 헤더 해시와 서명,
+//! 그리고 체인 끝에서 다음 문제를 정하는 규칙.
+//! 양쪽이 같은 코드를 써야 클라이언트가 서버가 낸 문제를 직접 다시 만들어 확인할 수 있다.
+
+pub mod accounts;
+pub mod body;
+pub mod chain;
+pub mod difficulty;
+pub mod emission;
+pub mod header;
+pub mod identity;
+pub mod ledger;
+pub mod pow;
+pub mod problem;
+pub mod protocol;
+pub mod puzzles;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod utxo;
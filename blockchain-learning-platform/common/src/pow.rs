@@ -0,0 +1,64 @@
+// common/src/pow.rs
+
+use crate::header::BlockHeader;
+use crate::problem::{Board, Problem};
+use crate::puzzles::SolutionError;
+
+/// 작업 증명 모드에서 라운드마다 브로드캐스트하는 문제
+///
+/// 판은 비어 있고 `difficulty`가 목표(해시 앞자리 0 비트 수)를 뜻한다.
+pub fn work_problem(target_bits: u32) -> Problem {
+    Problem::ProofOfWork(Board {
+        size: 0,
+        matrix: Vec::new(),
+        difficulty: target_bits,
+        solution_count: None,
+    })
+}
+
+/// 블록이 `target_bits`를 겨냥했고, 헤더 해시가 그 목표를 만족하는지 검사
+pub fn verify_work(header: &BlockHeader, target_bits: u32) -> Result<(), SolutionError> {
+    if !matches!(header.problem, Problem::ProofOfWork(_)) {
+        return Err(SolutionError::InvalidProblem);
+    }
+    if header.difficulty_target != target_bits {
+        return Err(SolutionError::TargetMismatch {
+            expected: target_bits,
+            actual: header.difficulty_target,
+        });
+    }
+    let hash = header.hash();
+    let actual = leading_zero_bits(&hash);
+    if actual < target_bits {
+        return Err(SolutionError::InsufficientWork {
+            hash: hex::encode(hash),
+            target: target_bits,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// 해시 앞쪽의 0 비트 수
+pub fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for &byte in hash {
+        bits += byte.leading_zeros();
+        if byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0xff]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x10]), 11);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+    }
+}
@@ -0,0 +1,148 @@
+// common/src/problem.rs
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::body::Transaction;
+use crate::chain::{default_chain_id, default_max_block_transactions, IssueRules};
+use crate::emission::Emission;
+use crate::ledger::LedgerModel;
+use crate::puzzles::PuzzleKind;
+
+// ------------------------------
+// 문제: 퍼즐 종류별로 태그가 붙은 열거형
+// JSON 예: {"kind": "sudoku", "puzzle": {"size": 4, "matrix": [...], "difficulty": 4}}
+// ------------------------------
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "puzzle", rename_all = "snake_case")]
+pub enum Problem {
+    MagicSquare(Board),
+    LatinSquare(Board),
+    Sudoku(Board),
+    #[serde(rename = "kenken")]
+    KenKen { board: Board, cages: Vec<Cage> },
+    /// 작업 증명 모드: 판은 비어 있고 `difficulty`가 목표 비트 수
+    ProofOfWork(Board),
+}
+
+/// 서버가 라운드마다 브로드캐스트하는 문제
+/// 블록과 투표는 `id`로 어느 라운드를 겨냥했는지 밝히고, 서버는 현재 라운드가 아니면 거절
+/// JSON 예: {"id": 3, "height": 2, "problem": {"kind": "sudoku", "puzzle": {...}}, "transactions": [...]}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Round {
+    pub id: u64,          // 서버가 문제를 낼 때마다 1씩 증가 (0 = 라운드 없음)
+    pub height: u64,      // 이 문제의 풀이로 만들 블록의 높이
+    pub problem: Problem,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>, // 블록 템플릿: 이 블록에 담을 대기 중인 거래 (멤풀 순서)
+    #[serde(default)]
+    pub ledger: LedgerModel,            // 이 체인의 원장 모델 (잔액과 거래를 검사하는 규칙)
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u32,                  // 거래에 넣어야 하는 체인 아이디
+    #[serde(default)]
+    pub emission: Emission,             // 코인베이스에 넣어야 하는 블록 보상 규칙
+    #[serde(default)]
+    pub rules: IssueRules,              // 문제를 내는 규칙 (검증 노드가 블록의 문제를 다시 만들어 봄)
+    #[serde(default = "default_max_block_transactions")]
+    pub max_transactions: usize,        // 블록 용량: 코인베이스를 뺀 최대 거래 수 (넘는 블록은 거절)
+}
+
+/// 블록이나 투표가 지금 진행 중인 라운드를 겨냥하지 않은 이유
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RoundError {
+    #[error("round {submitted} is not the active round ({active})")]
+    StaleRound { active: u64, submitted: u64 },
+    #[error("round {round} expired before anyone committed a block")]
+    RoundExpired { round: u64 },
+}
+
+/// 모든 퍼즐이 공유하는 n x n 판
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Board {
+    pub size: usize,           // 한 변의 길이 (n x n)
+    pub matrix: Vec<Vec<u32>>, // 주어진 숫자, 0은 빈 칸
+    pub difficulty: u32,       // 문제를 낼 때의 난이도 (빈 칸 수)
+    /// 빈 칸을 채우는 올바른 풀이 수 (1 = 유일한 풀이)
+    /// 여러 풀이 모드에서는 `MAX_COUNTED_SOLUTIONS`까지만 세고, 탐색 한도를 넘으면 `None`
+    pub solution_count: Option<u32>,
+}
+
+/// KenKen 케이지: 묶인 칸들의 값을 `op`로 계산하면 `target`이 되어야 함
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cage {
+    pub cells: Vec<(usize, usize)>,
+    pub op: CageOp,
+    pub target: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CageOp {
+    Given, // 한 칸짜리 케이지: 값 그대로
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl CageOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CageOp::Given => "",
+            CageOp::Add => "+",
+            CageOp::Sub => "-",
+            CageOp::Mul => "×",
+            CageOp::Div => "÷",
+        }
+    }
+}
+
+impl Problem {
+    /// 빈 n x n 마방진 문제 (제네시스 블록, 문제 수신 전 기본값)
+    pub fn empty(size: usize) -> Self {
+        Problem::MagicSquare(Board { size, matrix: vec![vec![0; size]; size], difficulty: 0, solution_count: None })
+    }
+
+    /// 퍼즐 종류 (작업 증명 문제는 `None`)
+    pub fn kind(&self) -> Option<PuzzleKind> {
+        match self {
+            Problem::MagicSquare(_) => Some(PuzzleKind::MagicSquare),
+            Problem::LatinSquare(_) => Some(PuzzleKind::LatinSquare),
+            Problem::Sudoku(_) => Some(PuzzleKind::Sudoku),
+            Problem::KenKen { .. } => Some(PuzzleKind::KenKen),
+            Problem::ProofOfWork(_) => None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        match self {
+            Problem::MagicSquare(board)
+            | Problem::LatinSquare(board)
+            | Problem::Sudoku(board)
+            | Problem::KenKen { board, .. }
+            | Problem::ProofOfWork(board) => board,
+        }
+    }
+
+    pub fn board_mut(&mut self) -> &mut Board {
+        match self {
+            Problem::MagicSquare(board)
+            | Problem::LatinSquare(board)
+            | Problem::Sudoku(board)
+            | Problem::KenKen { board, .. }
+            | Problem::ProofOfWork(board) => board,
+        }
+    }
+
+    /// 화면에 표시할 퍼즐 이름
+    pub fn title(&self) -> &'static str {
+        match self {
+            Problem::MagicSquare(_) => "Magic Square",
+            Problem::LatinSquare(_) => "Latin Square",
+            Problem::Sudoku(_) => "Sudoku",
+            Problem::KenKen { .. } => "KenKen",
+            Problem::ProofOfWork(_) => "Proof of Work",
+        }
+    }
+}
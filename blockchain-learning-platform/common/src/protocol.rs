@@ -0,0 +1,300 @@
+// common/src/protocol.rs
+
+//! 서버와 클라이언트가 주고받고 클라이언트가 RocksDB 에 저장하는 타입 (둘 다 JSON)
+//!
+//! `Problem`은 태그가 붙은 열거형이라 bincode 로는 다시 읽을 수 없으므로 저장도 JSON 으로 한다.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::chain::{genesis_body, genesis_hash, BlockError, ChainTip, GENESIS_NODE_ID, GENESIS_PREV_HASH, GENESIS_TIMESTAMP};
+use crate::header::BlockHeader;
+use crate::identity::SignatureError;
+
+pub use crate::body::{BlockBody, Coinbase, Transaction, TransactionError, BLOCK_REWARD};
+pub use crate::chain::IssueRules;
+pub use crate::emission::Emission;
+pub use crate::ledger::LedgerModel;
+pub use crate::utxo::OutPoint;
+pub use crate::problem::{Board, Cage, CageOp, Problem, Round, RoundError};
+
+/// 메시지 모양이 바뀔 때마다 올리는 프로토콜 버전
+/// 서버는 WebSocket 메시지에 담아 보내고, 클라이언트는 다르면 경고를 남김
+pub const PROTOCOL_VERSION: u32 = 11;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    pub index: u64,
+    pub prev_hash: String,       // 부모 블록 해시 (hex)
+    pub hash: String,            // 이 블록의 헤더 해시 (hex)
+    pub timestamp: u64,          // 블록을 만든 시각 (UTC 유닉스 시각, 밀리초)
+    pub problem: Problem,        // 블록에 포함된 문제
+    pub solution: Vec<Vec<u32>>, // 노드가 제출한 풀이
+    pub prev_solution: Vec<Vec<u32>>,
+    pub node_id: String,         // 블록을 낸 노드의 공개 키 (hex)
+    pub merkle_root: String,     // `body`의 머클 루트 (hex, 헤더에 들어감)
+    pub body: BlockBody,         // 코인베이스 보상과 거래 목록
+    pub difficulty: u32,         // 블록을 만들 때의 난이도 (빈 칸 수)
+    #[serde(default)]
+    pub nonce: u64,              // 작업 증명 모드: 헤더 해시가 목표를 만족하도록 고른 값
+    #[serde(default)]
+    pub difficulty_target: u32,  // 작업 증명 모드: 헤더 해시 앞자리 0 비트 수 (퍼즐 모드는 0)
+    #[serde(default)]
+    pub round_id: u64,           // 이 블록이 푼 문제의 라운드 (`Round::id`, 제네시스는 0)
+    #[serde(default)]
+    pub signature: String,       // `node_id` 키로 블록 해시에 한 Ed25519 서명 (hex, 제네시스는 빈 값)
+}
+
+impl Block {
+    /// 모든 노드가 같은 해시를 갖는 고정된 제네시스 블록
+    pub fn genesis() -> Self {
+        Block {
+            index: 0,
+            prev_hash: GENESIS_PREV_HASH.to_string(),
+            hash: hex::encode(genesis_hash()),
+            timestamp: GENESIS_TIMESTAMP,
+            problem: Problem::empty(0),
+            solution: vec![],
+            prev_solution: vec![],
+            node_id: GENESIS_NODE_ID.to_string(),
+            merkle_root: genesis_body().merkle_root_hex(),
+            body: genesis_body(),
+            difficulty: 0,
+            nonce: 0,
+            difficulty_target: 0,
+            round_id: 0,
+            signature: String::new(),
+        }
+    }
+
+    /// 새 블록을 만들고 머클 루트와 헤더 해시를 채움 (nonce, 라운드, 서명은 비어 있음)
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        index: u64,
+        prev_hash: String,
+        timestamp: u64,
+        problem: Problem,
+        solution: Vec<Vec<u32>>,
+        prev_solution: Vec<Vec<u32>>,
+        node_id: String,
+        body: BlockBody,
+    ) -> Self {
+        let mut block = Block {
+            index,
+            prev_hash,
+            hash: String::new(),
+            timestamp,
+            difficulty: problem.board().difficulty,
+            problem,
+            solution,
+            prev_solution,
+            node_id,
+            merkle_root: body.merkle_root_hex(),
+            body,
+            nonce: 0,
+            difficulty_target: 0,
+            round_id: 0,
+            signature: String::new(),
+        };
+        block.seal();
+        block
+    }
+
+    /// 블록 해시에 들어가는 헤더 (`round_id`, `prev_solution`, `signature` 는 제외, 본문은 머클 루트로만 들어감)
+    pub fn header(&self) -> BlockHeader<'_> {
+        BlockHeader {
+            index: self.index,
+            prev_hash: &self.prev_hash,
+            timestamp: self.timestamp,
+            node_id: &self.node_id,
+            merkle_root: &self.merkle_root,
+            problem: &self.problem,
+            solution: &self.solution,
+            difficulty: self.difficulty,
+            difficulty_target: self.difficulty_target,
+            nonce: self.nonce,
+        }
+    }
+
+    /// 헤더로 다시 계산한 블록 해시 (작업 증명 목표, 다음 문제의 시드에 쓰임)
+    pub fn compute_hash(&self) -> [u8; 32] {
+        self.header().hash()
+    }
+
+    /// 헤더나 본문을 바꾼 뒤 `merkle_root`와 `hash`를 다시 채움
+    pub fn seal(&mut self) {
+        self.merkle_root = self.body.merkle_root_hex();
+        self.hash = hex::encode(self.compute_hash());
+    }
+
+    /// 헤더의 머클 루트가 본문과 맞는지 (본문은 헤더 해시에 머클 루트로만 묶임)
+    pub fn check_merkle_root(&self) -> Result<(), BlockError> {
+        let expected = self.body.merkle_root_hex();
+        if self.merkle_root != expected {
+            return Err(BlockError::MerkleRootMismatch { expected, actual: self.merkle_root.clone() });
+        }
+        Ok(())
+    }
+
+    /// 이 블록을 체인 끝으로 볼 때의 높이와 해시 (다음 문제의 시드)
+    pub fn as_tip(&self) -> ChainTip {
+        ChainTip { height: self.index, hash: self.compute_hash() }
+    }
+}
+
+/// 블록에 대한 한 노드의 검증 투표
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub is_valid: bool,
+    pub node_id: String,         // 투표한 노드의 공개 키 (hex)
+    #[serde(default)]
+    pub round_id: u64,           // 투표한 블록의 라운드
+    #[serde(default)]
+    pub block_hash: String,      // 투표한 블록의 해시 (hex)
+    #[serde(default)]
+    pub signature: String,       // `node_id` 키로 (round_id, block_hash, is_valid)에 한 서명 (hex)
+}
+
+/// 서버가 투표를 합의에 넣지 않는 이유
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum VoteError {
+    #[error("there is no pending block to vote on")]
+    NoPendingBlock,
+    #[error("vote is for block {actual}, the pending block is {expected}")]
+    VoteForOtherBlock { expected: String, actual: String },
+    #[error("node {node_id} has already voted on this block")]
+    DuplicateVote { node_id: String },
+    #[error(transparent)]
+    #[serde(untagged)]
+    Round(#[from] RoundError),
+    #[error(transparent)]
+    #[serde(untagged)]
+    Signature(#[from] SignatureError),
+}
+
+/// WebSocket 으로 서버가 보내는 메시지
+/// JSON 예: {"type": "problem", "data": {...}, "version": PROTOCOL_VERSION}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Problem(Round),
+    Block(Block),
+    /// 멤풀이 바뀔 때마다 보내는 대기 중인 거래 전체 (멤풀 순서)
+    Mempool(Vec<Transaction>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pow::work_problem;
+    use serde::de::DeserializeOwned;
+    use std::fmt::Debug;
+
+    fn block() -> Block {
+        let problem = Problem::KenKen {
+            board: Board { size: 2, matrix: vec![vec![1, 0], vec![0, 1]], difficulty: 2, solution_count: Some(1) },
+            cages: vec![Cage { cells: vec![(0, 1), (1, 0)], op: CageOp::Add, target: 4 }],
+        };
+        let mut block = Block::new(
+            1,
+            hex::encode(genesis_hash()),
+            GENESIS_TIMESTAMP + 1_000,
+            problem,
+            vec![vec![1, 2], vec![2, 1]],
+            vec![],
+            "node".to_string(),
+            BlockBody {
+                coinbase: Coinbase { receiver_id: "node".to_string(), amount: BLOCK_REWARD },
+                transactions: vec![Transaction::new("a", "b", 3)],
+            },
+        );
+        block.round_id = 7;
+        block.signature = "ab".repeat(64);
+        block
+    }
+
+    fn json_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value, "{}", json);
+    }
+
+    #[test]
+    fn wire_types_survive_json() {
+        json_round_trip(&block());
+        json_round_trip(&Block::genesis());
+        json_round_trip(&ValidationResult {
+            is_valid: true,
+            node_id: "node".to_string(),
+            round_id: 7,
+            block_hash: block().hash,
+            signature: "cd".repeat(64),
+        });
+        json_round_trip(&Transaction {
+            fee: 3,
+            nonce: 4,
+            chain_id: 9,
+            signature: "ab".repeat(64),
+            ..Transaction::new("a", "b", u64::MAX)
+        });
+        json_round_trip(&Transaction {
+            inputs: vec![OutPoint { txid: "ef".repeat(32), vout: 1 }],
+            ..Transaction::new("a", "b", 2)
+        });
+        json_round_trip(&ServerMessage::Block(block()));
+        json_round_trip(&ServerMessage::Mempool(block().body.transactions));
+        json_round_trip(&ServerMessage::Problem(Round {
+            id: 3,
+            height: 2,
+            problem: work_problem(12),
+            transactions: vec![Transaction::new("a", "b", 1)],
+            ledger: LedgerModel::Utxo,
+            chain_id: 9,
+            emission: Emission::Halving { initial: 50, interval: 210 },
+            rules: IssueRules { work_target: Some(12), ..IssueRules::default() },
+            max_transactions: 1,
+        }));
+    }
+
+    #[test]
+    fn stored_blocks_survive_json_bytes() {
+        for block in [Block::genesis(), block()] {
+            let bytes = serde_json::to_vec(&block).unwrap();
+            assert_eq!(serde_json::from_slice::<Block>(&bytes).unwrap(), block);
+        }
+    }
+
+    #[test]
+    fn server_messages_use_lowercase_tags() {
+        let message = serde_json::to_value(ServerMessage::Block(Block::genesis())).unwrap();
+        assert_eq!(message["type"], "block");
+        let round = Round { id: 1, height: 1, problem: Problem::empty(4), transactions: vec![], ledger: LedgerModel::Account, chain_id: 1, emission: Emission::default(), rules: IssueRules::default(), max_transactions: 100 };
+        let message = serde_json::to_value(ServerMessage::Problem(round)).unwrap();
+        assert_eq!(message["type"], "problem");
+        assert_eq!(message["data"]["problem"]["kind"], "magic_square");
+        let message = serde_json::to_value(ServerMessage::Mempool(vec![])).unwrap();
+        assert_eq!(message["type"], "mempool");
+    }
+
+    #[test]
+    fn sealed_blocks_hash_their_header() {
+        let mut block = block();
+        assert_eq!(block.hash, hex::encode(block.compute_hash()));
+        block.nonce += 1;
+        assert_ne!(block.hash, hex::encode(block.compute_hash()));
+        block.seal();
+        assert_eq!(block.hash, hex::encode(block.compute_hash()));
+    }
+
+    #[test]
+    fn body_changes_must_be_resealed() {
+        let mut block = block();
+        assert_eq!(block.check_merkle_root(), Ok(()));
+        block.body.transactions[0].amount = 300;
+        assert!(matches!(block.check_merkle_root(), Err(BlockError::MerkleRootMismatch { .. })));
+        let hash = block.hash.clone();
+        block.seal();
+        assert_eq!(block.check_merkle_root(), Ok(()));
+        assert_ne!(block.hash, hash);
+    }
+}
@@ -0,0 +1,154 @@
+// common/src/puzzles/kenken.rs
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use super::latin_square::random_latin_square;
+use super::solver::count_latin_solutions;
+use super::{
+    carve, check_givens, check_latin, check_range, check_shape, GenerateParams, Puzzle, SolutionError, SEARCH_BUDGET,
+};
+use crate::difficulty;
+use crate::problem::{Board, Cage, CageOp, Problem};
+
+/// 케이지 하나에 묶을 수 있는 최대 칸 수
+const MAX_CAGE_CELLS: usize = 4;
+
+/// KenKen: 라틴 방진 조건에 더해 케이지마다 연산 결과를 맞추는 퍼즐
+pub struct KenKenPuzzle;
+
+impl Puzzle for KenKenPuzzle {
+    fn generate(&self, rng: &mut dyn RngCore, params: &GenerateParams) -> Problem {
+        let (size, blanks) = difficulty::layout(params.difficulty, params.min_size, params.max_size, |n| n >= 3);
+        let matrix = random_latin_square(rng, size);
+        let cages = random_cages(rng, &matrix);
+        let board = Board { size, matrix, difficulty: params.difficulty, solution_count: None };
+        carve(self, rng, Problem::KenKen { board, cages }, blanks, params.unique)
+    }
+
+    fn verify(&self, problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+        let Problem::KenKen { board, cages } = problem else {
+            return Err(SolutionError::InvalidProblem);
+        };
+        let n = check_shape(board, solution)?;
+        check_givens(&board.matrix, solution)?;
+        check_range(solution, n as u32)?;
+        check_latin(solution)?;
+
+        for (index, cage) in cages.iter().enumerate() {
+            if cage.cells.iter().any(|&(i, j)| i >= n || j >= n) {
+                return Err(SolutionError::InvalidProblem);
+            }
+            let values: Vec<u32> = cage.cells.iter().map(|&(i, j)| solution[i][j]).collect();
+            if evaluate(cage.op, &values) != Some(cage.target) {
+                return Err(SolutionError::CageMismatch {
+                    index,
+                    target: cage.target,
+                    op: cage.op.symbol().to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self, problem: &Problem) -> String {
+        let n = problem.board().size;
+        let cages = match problem {
+            Problem::KenKen { cages, .. } => cages.len(),
+            _ => 0,
+        };
+        format!("{n}x{n} kenken: a latin square of 1..={n} whose {cages} cages must reach their targets")
+    }
+
+    fn count_solutions(&self, problem: &Problem, limit: usize) -> Option<usize> {
+        let Problem::KenKen { board, cages } = problem else {
+            return Some(0);
+        };
+        count_latin_solutions(&board.matrix, None, cages, limit, SEARCH_BUDGET)
+    }
+}
+
+/// 케이지 연산 결과 (뺄셈/나눗셈은 두 칸, 나눗셈은 나누어떨어질 때만)
+pub fn evaluate(op: CageOp, values: &[u32]) -> Option<u32> {
+    match (op, values) {
+        (CageOp::Given, [value]) => Some(*value),
+        (CageOp::Add, _) => Some(values.iter().sum()),
+        (CageOp::Mul, _) => Some(values.iter().product()),
+        (CageOp::Sub, [a, b]) => Some(a.abs_diff(*b)),
+        (CageOp::Div, [a, b]) => {
+            let (big, small) = if a >= b { (a, b) } else { (b, a) };
+            (big % small == 0).then(|| big / small)
+        }
+        _ => None,
+    }
+}
+
+/// 완성된 풀이를 무작위로 케이지로 나누고 각 케이지의 연산과 목표값을 정함
+fn random_cages(rng: &mut dyn RngCore, solution: &[Vec<u32>]) -> Vec<Cage> {
+    let n = solution.len();
+    let mut owner: Vec<Vec<Option<usize>>> = vec![vec![None; n]; n];
+    let mut cells_of: Vec<Vec<(usize, usize)>> = Vec::new();
+
+    let mut order: Vec<(usize, usize)> = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).collect();
+    order.shuffle(rng);
+
+    for &(i, j) in &order {
+        if owner[i][j].is_some() {
+            continue;
+        }
+        // 빈 이웃 칸으로 케이지를 키워 나감
+        let cage = cells_of.len();
+        let target_len = rng.gen_range(1..=MAX_CAGE_CELLS);
+        let mut cells = vec![(i, j)];
+        owner[i][j] = Some(cage);
+        while cells.len() < target_len {
+            let mut frontier: Vec<(usize, usize)> = cells
+                .iter()
+                .flat_map(|&(ci, cj)| neighbours(ci, cj, n))
+                .filter(|&(ni, nj)| owner[ni][nj].is_none())
+                .collect();
+            frontier.sort_unstable();
+            frontier.dedup();
+            let Some(&(ni, nj)) = frontier.choose(rng) else {
+                break;
+            };
+            owner[ni][nj] = Some(cage);
+            cells.push((ni, nj));
+        }
+        cells_of.push(cells);
+    }
+
+    cells_of
+        .into_iter()
+        .map(|mut cells| {
+            cells.sort_unstable();
+            let values: Vec<u32> = cells.iter().map(|&(i, j)| solution[i][j]).collect();
+            let op = pick_op(rng, &values);
+            let target = evaluate(op, &values).expect("picked op applies to the cage");
+            Cage { cells, op, target }
+        })
+        .collect()
+}
+
+/// 케이지 크기와 값에 맞는 연산을 무작위로 고름
+fn pick_op(rng: &mut dyn RngCore, values: &[u32]) -> CageOp {
+    match values {
+        [_] => CageOp::Given,
+        [a, b] => {
+            let mut ops = vec![CageOp::Add, CageOp::Sub, CageOp::Mul];
+            if a.max(b) % a.min(b) == 0 {
+                ops.push(CageOp::Div);
+            }
+            *ops.choose(rng).expect("ops is not empty")
+        }
+        _ => *[CageOp::Add, CageOp::Mul].choose(rng).expect("ops is not empty"),
+    }
+}
+
+fn neighbours(i: usize, j: usize, n: usize) -> impl Iterator<Item = (usize, usize)> {
+    let up = i.checked_sub(1).map(|i| (i, j));
+    let down = (i + 1 < n).then_some((i + 1, j));
+    let left = j.checked_sub(1).map(|j| (i, j));
+    let right = (j + 1 < n).then_some((i, j + 1));
+    [up, down, left, right].into_iter().flatten()
+}
@@ -0,0 +1,56 @@
+// common/src/puzzles/latin_square.rs
+
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use super::solver::count_latin_solutions;
+use super::{
+    carve, check_givens, check_latin, check_range, check_shape, GenerateParams, Puzzle, SolutionError, SEARCH_BUDGET,
+};
+use crate::difficulty;
+use crate::problem::{Board, Problem};
+
+/// 라틴 방진: 각 행과 열에 1..=n 을 한 번씩 배치하는 퍼즐
+pub struct LatinSquarePuzzle;
+
+impl Puzzle for LatinSquarePuzzle {
+    fn generate(&self, rng: &mut dyn RngCore, params: &GenerateParams) -> Problem {
+        let (size, blanks) = difficulty::layout(params.difficulty, params.min_size, params.max_size, |n| n >= 2);
+        let matrix = random_latin_square(rng, size);
+        let board = Board { size, matrix, difficulty: params.difficulty, solution_count: None };
+        carve(self, rng, Problem::LatinSquare(board), blanks, params.unique)
+    }
+
+    fn verify(&self, problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+        let n = check_shape(problem.board(), solution)?;
+        check_givens(&problem.board().matrix, solution)?;
+        check_range(solution, n as u32)?;
+        check_latin(solution)
+    }
+
+    fn describe(&self, problem: &Problem) -> String {
+        let n = problem.board().size;
+        format!("{n}x{n} latin square: every row and column holds 1..={n} exactly once")
+    }
+
+    fn count_solutions(&self, problem: &Problem, limit: usize) -> Option<usize> {
+        count_latin_solutions(&problem.board().matrix, None, &[], limit, SEARCH_BUDGET)
+    }
+}
+
+/// 무작위 n x n 라틴 방진
+///
+/// 순환 라틴 방진 (i + j) mod n 에서 행, 열, 숫자를 각각 무작위로 섞는다.
+/// (세 가지 순열 모두 라틴 방진 성질을 유지함)
+pub fn random_latin_square(rng: &mut dyn RngCore, n: usize) -> Vec<Vec<u32>> {
+    let mut rows: Vec<usize> = (0..n).collect();
+    let mut cols: Vec<usize> = (0..n).collect();
+    let mut symbols: Vec<u32> = (1..=n as u32).collect();
+    rows.shuffle(rng);
+    cols.shuffle(rng);
+    symbols.shuffle(rng);
+
+    rows.iter()
+        .map(|&i| cols.iter().map(|&j| symbols[(i + j) % n]).collect())
+        .collect()
+}
@@ -0,0 +1,512 @@
+// common/src/puzzles/magic_square.rs
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use std::sync::OnceLock;
+
+use super::{carve, check_givens, check_shape, GenerateParams, Puzzle, SolutionError, SEARCH_BUDGET};
+use crate::difficulty;
+use crate::problem::{Board, Problem};
+
+/// 마방진: 1..=n² 을 한 번씩 써서 모든 행, 열, 대각선의 합을 같게 만드는 퍼즐
+pub struct MagicSquarePuzzle;
+
+impl Puzzle for MagicSquarePuzzle {
+    fn generate(&self, rng: &mut dyn RngCore, params: &GenerateParams) -> Problem {
+        // 2x2 마방진은 존재하지 않음
+        let (size, blanks) = difficulty::layout(params.difficulty, params.min_size, params.max_size, |n| n != 2);
+        let matrix = generate_random_magic_square(rng, size);
+        let board = Board { size, matrix, difficulty: params.difficulty, solution_count: None };
+        carve(self, rng, Problem::MagicSquare(board), blanks, params.unique)
+    }
+
+    fn verify(&self, problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+        check_shape(problem.board(), solution)?;
+        verify_solution(&problem.board().matrix, solution)
+    }
+
+    fn describe(&self, problem: &Problem) -> String {
+        let n = problem.board().size;
+        format!(
+            "{n}x{n} magic square: use 1..={} once each so every row, column and diagonal sums to {}",
+            n * n,
+            magic_constant(n)
+        )
+    }
+
+    fn count_solutions(&self, problem: &Problem, limit: usize) -> Option<usize> {
+        count_completions(&problem.board().matrix, limit, SEARCH_BUDGET)
+    }
+}
+
+/// n x n 마방진의 마법 상수: n(n² + 1) / 2
+pub fn magic_constant(n: usize) -> u32 {
+    let n = n as u32;
+    n * (n * n + 1) / 2
+}
+
+/// 문제(`0`은 빈 칸)에 대한 풀이가 올바른 마방진인지 검사
+///
+/// 1) 문제에 주어진 숫자를 그대로 유지하는지
+/// 2) 1..=n² 의 숫자를 정확히 한 번씩 사용하는지
+/// 3) 모든 행, 열, 두 대각선의 합이 마법 상수와 같은지
+pub fn verify_solution(problem: &[Vec<u32>], solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+    let n = problem.len();
+    if n == 0 || problem.iter().any(|row| row.len() != n) {
+        return Err(SolutionError::InvalidProblem);
+    }
+    if solution.len() != n || solution.iter().any(|row| row.len() != n) {
+        return Err(SolutionError::InvalidShape { expected: n });
+    }
+
+    // 1) 주어진 칸 유지
+    check_givens(problem, solution)?;
+
+    // 2) 1..=n² 을 한 번씩만 사용
+    let max = (n * n) as u32;
+    let mut seen = vec![false; n * n + 1];
+    for &value in solution.iter().flatten() {
+        if value == 0 || value > max {
+            return Err(SolutionError::OutOfRange { value, max });
+        }
+        if std::mem::replace(&mut seen[value as usize], true) {
+            return Err(SolutionError::DuplicateValue { value });
+        }
+    }
+
+    // 3) 행, 열, 대각선 합
+    let expected = magic_constant(n);
+    for (index, row) in solution.iter().enumerate() {
+        let actual = row.iter().sum();
+        if actual != expected {
+            return Err(SolutionError::RowSum { index, expected, actual });
+        }
+    }
+    for index in 0..n {
+        let actual = solution.iter().map(|row| row[index]).sum();
+        if actual != expected {
+            return Err(SolutionError::ColumnSum { index, expected, actual });
+        }
+    }
+    let actual = (0..n).map(|i| solution[i][i]).sum();
+    if actual != expected {
+        return Err(SolutionError::DiagonalSum { expected, actual });
+    }
+    let actual = (0..n).map(|i| solution[i][n - 1 - i]).sum();
+    if actual != expected {
+        return Err(SolutionError::AntiDiagonalSum { expected, actual });
+    }
+
+    Ok(())
+}
+
+/// 빈 칸(`0`)을 채워 가며 가능한 마방진을 모두 찾는 백트래킹 탐색
+///
+/// 매 단계마다 빈 칸이 가장 적은 줄(행/열/대각선)을 골라 채우고,
+/// 빈 칸이 하나 남은 줄은 남은 합으로 값이 정해지므로 후보를 하나로 줄인다.
+struct Search {
+    n: usize,
+    target: u32,
+    max: u32,
+    grid: Vec<u32>,
+    used: Vec<bool>,
+    /// 각 줄에 속한 칸 (행 n개, 열 n개, 대각선, 반대 대각선)
+    lines: Vec<Vec<usize>>,
+    /// 각 칸이 속한 줄
+    cell_lines: Vec<Vec<usize>>,
+    sums: Vec<u32>,
+    empties: Vec<usize>,
+    /// 남은 탐색 노드 수, 다 쓰면 `exhausted`
+    nodes_left: usize,
+    exhausted: bool,
+}
+
+impl Search {
+    /// 문제에 주어진 숫자가 중복되거나 범위를 벗어나거나 합이 맞지 않으면 `None`
+    fn new(problem: &[Vec<u32>]) -> Option<Self> {
+        let n = problem.len();
+        if n == 0 || problem.iter().any(|row| row.len() != n) {
+            return None;
+        }
+
+        let mut lines: Vec<Vec<usize>> = Vec::with_capacity(2 * n + 2);
+        lines.extend((0..n).map(|i| (0..n).map(|j| i * n + j).collect()));
+        lines.extend((0..n).map(|j| (0..n).map(|i| i * n + j).collect()));
+        lines.push((0..n).map(|i| i * n + i).collect());
+        lines.push((0..n).map(|i| i * n + (n - 1 - i)).collect());
+
+        let mut cell_lines = vec![Vec::new(); n * n];
+        for (line, cells) in lines.iter().enumerate() {
+            for &cell in cells {
+                cell_lines[cell].push(line);
+            }
+        }
+
+        let mut search = Search {
+            n,
+            target: magic_constant(n),
+            max: (n * n) as u32,
+            grid: vec![0; n * n],
+            used: vec![false; n * n + 1],
+            sums: vec![0; lines.len()],
+            empties: lines.iter().map(|cells| cells.len()).collect(),
+            lines,
+            cell_lines,
+            nodes_left: usize::MAX,
+            exhausted: false,
+        };
+
+        for (cell, &value) in problem.iter().flatten().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            if value > search.max || search.used[value as usize] || !search.fits(cell, value) {
+                return None;
+            }
+            search.place(cell, value);
+        }
+        Some(search)
+    }
+
+    /// 남은 빈 칸 k개로 줄의 합을 정확히 맞출 수 있는지
+    fn fits(&self, cell: usize, value: u32) -> bool {
+        self.cell_lines[cell].iter().all(|&line| {
+            let sum = self.sums[line] + value;
+            let k = (self.empties[line] - 1) as u32;
+            if k == 0 {
+                return sum == self.target;
+            }
+            // 서로 다른 k개의 수로 만들 수 있는 최소/최대 합
+            let min_rest = k * (k + 1) / 2;
+            let max_rest = k * self.max - k * (k - 1) / 2;
+            sum + min_rest <= self.target && sum + max_rest >= self.target
+        })
+    }
+
+    fn place(&mut self, cell: usize, value: u32) {
+        self.grid[cell] = value;
+        self.used[value as usize] = true;
+        for &line in &self.cell_lines[cell] {
+            self.sums[line] += value;
+            self.empties[line] -= 1;
+        }
+    }
+
+    fn unplace(&mut self, cell: usize) {
+        let value = std::mem::take(&mut self.grid[cell]);
+        self.used[value as usize] = false;
+        for &line in &self.cell_lines[cell] {
+            self.sums[line] -= value;
+            self.empties[line] += 1;
+        }
+    }
+
+    fn rows(&self) -> Vec<Vec<u32>> {
+        self.grid.chunks(self.n).map(|row| row.to_vec()).collect()
+    }
+
+    /// 해를 찾을 때마다 `on_solution` 호출 (`true`를 돌려주면 탐색 중단)
+    fn run(&mut self, on_solution: &mut dyn FnMut(&[Vec<u32>]) -> bool) -> bool {
+        if self.nodes_left == 0 {
+            self.exhausted = true;
+            return true;
+        }
+        self.nodes_left -= 1;
+
+        let next_line = (0..self.lines.len())
+            .filter(|&line| self.empties[line] > 0)
+            .min_by_key(|&line| self.empties[line]);
+        let Some(line) = next_line else {
+            return on_solution(&self.rows());
+        };
+        let cell = self.lines[line]
+            .iter()
+            .copied()
+            .find(|&cell| self.grid[cell] == 0)
+            .expect("line has an empty cell");
+
+        // 빈 칸이 하나 남은 줄은 값이 하나로 정해짐
+        let candidates: Vec<u32> = if self.empties[line] == 1 {
+            vec![self.target.saturating_sub(self.sums[line])]
+        } else {
+            (1..=self.max).collect()
+        };
+
+        for value in candidates {
+            if value == 0 || value > self.max || self.used[value as usize] || !self.fits(cell, value) {
+                continue;
+            }
+            self.place(cell, value);
+            let stop = self.run(on_solution);
+            self.unplace(cell);
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// 문제의 빈 칸을 채우는 모든 마방진 완성을 `on_solution`으로 넘김
+/// (`on_solution`이 `true`를 돌려주면 중단)
+pub fn for_each_completion(problem: &[Vec<u32>], mut on_solution: impl FnMut(&[Vec<u32>]) -> bool) {
+    if let Some(mut search) = Search::new(problem) {
+        search.run(&mut on_solution);
+    }
+}
+
+/// 문제의 마방진 완성 수를 `limit`개까지 셈
+/// 탐색 노드가 `budget`을 넘으면 `None`
+pub fn count_completions(problem: &[Vec<u32>], limit: usize, budget: usize) -> Option<usize> {
+    let Some(mut search) = Search::new(problem) else {
+        return Some(0);
+    };
+    search.nodes_left = budget;
+    let mut found = 0;
+    search.run(&mut |_| {
+        found += 1;
+        found >= limit
+    });
+    (!search.exhausted).then_some(found)
+}
+
+/// 4x4 마방진 전체 (880개의 본질적으로 다른 마방진 × 회전/반사 8가지 = 7040개)
+pub fn all_4x4_magic_squares() -> &'static [Vec<Vec<u32>>] {
+    static ALL: OnceLock<Vec<Vec<Vec<u32>>>> = OnceLock::new();
+    ALL.get_or_init(|| {
+        let mut squares = Vec::new();
+        for_each_completion(&vec![vec![0; 4]; 4], |square| {
+            squares.push(square.to_vec());
+            false
+        });
+        squares
+    })
+}
+
+/// 홀수 n: 시암(Siamese) 방법
+///
+/// 첫 행 가운데에서 시작해 오른쪽 위로 이동하며 채우고,
+/// 이미 채워진 칸이면 바로 아래 칸으로 내려간다.
+fn siamese(n: usize) -> Vec<Vec<u32>> {
+    let mut square = vec![vec![0; n]; n];
+    let (mut i, mut j) = (0, n / 2);
+    for value in 1..=(n * n) as u32 {
+        square[i][j] = value;
+        let (up, right) = ((i + n - 1) % n, (j + 1) % n);
+        if square[up][right] == 0 {
+            (i, j) = (up, right);
+        } else {
+            i = (i + 1) % n;
+        }
+    }
+    square
+}
+
+/// n이 4의 배수: 1..n² 을 차례로 채운 뒤 4x4 블록의 대각선 칸을 n²+1-x 로 뒤집음
+fn doubly_even(n: usize) -> Vec<Vec<u32>> {
+    let complement = (n * n + 1) as u32;
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let value = (i * n + j + 1) as u32;
+                    if i % 4 == j % 4 || i % 4 + j % 4 == 3 {
+                        complement - value
+                    } else {
+                        value
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// n = 4m + 2: 콘웨이의 LUX 방법
+///
+/// (2m+1) 크기의 시암 마방진의 각 칸을 L/U/X 모양의 2x2 블록으로 펼친다.
+/// 위쪽 m+1 행은 L, 그 다음 한 행은 U, 나머지 m-1 행은 X 이고
+/// 가운데 열의 L 하나와 그 아래 U 를 맞바꾼다.
+fn lux(n: usize) -> Vec<Vec<u32>> {
+    let m = (n - 2) / 4;
+    let k = 2 * m + 1;
+    let small = siamese(k);
+
+    // 2x2 블록 안에서 base+1..base+4 를 놓는 순서 [[왼위, 오위], [왼아래, 오아래]]
+    const L: [[u32; 2]; 2] = [[4, 1], [2, 3]];
+    const U: [[u32; 2]; 2] = [[1, 4], [2, 3]];
+    const X: [[u32; 2]; 2] = [[1, 4], [3, 2]];
+
+    let mut square = vec![vec![0; n]; n];
+    for (i, row) in small.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            let pattern = match i {
+                _ if i == m && j == m => &U,
+                _ if i == m + 1 && j == m => &L,
+                _ if i <= m => &L,
+                _ if i == m + 1 => &U,
+                _ => &X,
+            };
+            let base = 4 * (value - 1);
+            for (di, pattern_row) in pattern.iter().enumerate() {
+                for (dj, &offset) in pattern_row.iter().enumerate() {
+                    square[2 * i + di][2 * j + dj] = base + offset;
+                }
+            }
+        }
+    }
+    square
+}
+
+/// 마방진 성질을 유지하는 변환을 무작위로 적용
+///
+/// - 회전/반사 (8가지)
+/// - 보수 변환 x -> n²+1-x
+/// - 행과 열에 같은 순열 p 를 적용 (p(n-1-i) = n-1-p(i) 를 만족하면
+///   행/열 합과 두 대각선이 그대로 유지됨)
+fn shuffle_magic_square<R: Rng + ?Sized>(rng: &mut R, square: Vec<Vec<u32>>) -> Vec<Vec<u32>> {
+    let n = square.len();
+
+    // 대칭 쌍 {i, n-1-i} 를 섞고, 각 쌍의 방향도 무작위로 뒤집음
+    let half = n / 2;
+    let mut pairs: Vec<usize> = (0..half).collect();
+    pairs.shuffle(rng);
+    let mut p: Vec<usize> = (0..n).collect();
+    for (i, &pair) in pairs.iter().enumerate() {
+        let (a, b) = if rng.gen() { (pair, n - 1 - pair) } else { (n - 1 - pair, pair) };
+        p[i] = a;
+        p[n - 1 - i] = b;
+    }
+
+    let complement = rng.gen::<bool>();
+    let transpose = rng.gen::<bool>();
+    let flip_rows = rng.gen::<bool>();
+    let flip_cols = rng.gen::<bool>();
+    let max = (n * n) as u32;
+
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let (i, j) = if transpose { (j, i) } else { (i, j) };
+                    let i = if flip_rows { n - 1 - i } else { i };
+                    let j = if flip_cols { n - 1 - j } else { j };
+                    let value = square[p[i]][p[j]];
+                    if complement { max + 1 - value } else { value }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// n x n 마방진을 무작위로 생성
+///
+/// 4x4 는 전체 7040개 중에서 균등하게 뽑고, 나머지 크기는
+/// 홀수(시암), 4의 배수, 4m+2(LUX) 구성법으로 만든 뒤 무작위 변환을 적용한다.
+/// 2x2 마방진은 존재하지 않으므로 n == 2 는 허용하지 않는다.
+pub fn generate_random_magic_square<R: Rng + ?Sized>(rng: &mut R, n: usize) -> Vec<Vec<u32>> {
+    assert!(n == 1 || n >= 3, "no {n}x{n} magic square exists");
+    let base = match n {
+        4 => {
+            return all_4x4_magic_squares()
+                .choose(rng)
+                .cloned()
+                .expect("4x4 magic squares exist")
+        }
+        _ if n % 2 == 1 => siamese(n),
+        _ if n.is_multiple_of(4) => doubly_even(n),
+        _ => lux(n),
+    };
+    shuffle_magic_square(rng, base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::MAX_COUNTED_SOLUTIONS;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn enumerates_the_full_4x4_family() {
+        let all = all_4x4_magic_squares();
+        assert_eq!(all.len(), 7040);
+        let distinct: HashSet<_> = all.iter().collect();
+        assert_eq!(distinct.len(), 7040);
+    }
+
+    #[test]
+    fn every_enumerated_square_is_magic() {
+        let empty = vec![vec![0; 4]; 4];
+        for square in all_4x4_magic_squares() {
+            assert_eq!(verify_solution(&empty, square), Ok(()));
+        }
+    }
+
+    #[test]
+    fn rejects_the_broken_row_shuffle() {
+        // 예전 생성기처럼 행만 섞으면 대각선 합이 깨짐
+        let shuffled = vec![
+            vec![5, 11, 10, 8],
+            vec![16, 2, 3, 13],
+            vec![9, 7, 6, 12],
+            vec![4, 14, 15, 1],
+        ];
+        let empty = vec![vec![0; 4]; 4];
+        assert!(matches!(
+            verify_solution(&empty, &shuffled),
+            Err(SolutionError::DiagonalSum { .. })
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn generated_square_is_magic(seed in any::<u64>()) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let square = generate_random_magic_square(&mut rng, 4);
+            prop_assert_eq!(verify_solution(&vec![vec![0; 4]; 4], &square), Ok(()));
+        }
+
+        #[test]
+        fn generated_problem_has_a_unique_completion(seed in any::<u64>(), difficulty in 1u32..=12) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let params = GenerateParams { difficulty, min_size: 4, max_size: 4, unique: true };
+            let problem = MagicSquarePuzzle.generate(&mut rng, &params);
+            let matrix = &problem.board().matrix;
+            prop_assert_eq!(problem.board().solution_count, Some(1));
+
+            let mut completions = Vec::new();
+            for_each_completion(matrix, |square| {
+                completions.push(square.to_vec());
+                completions.len() > 1
+            });
+            prop_assert_eq!(completions.len(), 1);
+            prop_assert_eq!(verify_solution(matrix, &completions[0]), Ok(()));
+        }
+
+        #[test]
+        fn multi_solution_problem_records_its_solution_count(seed in any::<u64>(), difficulty in 1u32..=12) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let params = GenerateParams { difficulty, min_size: 4, max_size: 4, unique: false };
+            let problem = MagicSquarePuzzle.generate(&mut rng, &params);
+            let blanks = problem.board().matrix.iter().flatten().filter(|&&v| v == 0).count();
+            prop_assert_eq!(blanks, difficulty as usize);
+
+            let mut count = 0;
+            for_each_completion(&problem.board().matrix, |_| {
+                count += 1;
+                false
+            });
+            prop_assert!(count >= 1);
+            prop_assert_eq!(problem.board().solution_count, Some(count.min(MAX_COUNTED_SOLUTIONS) as u32));
+        }
+
+        #[test]
+        fn generated_nxn_square_is_magic(seed in any::<u64>(), n in prop_oneof![Just(1usize), 3usize..=14]) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let square = generate_random_magic_square(&mut rng, n);
+            prop_assert_eq!(verify_solution(&vec![vec![0; n]; n], &square), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,313 @@
+// common/src/puzzles/mod.rs
+
+pub mod kenken;
+pub mod latin_square;
+pub mod magic_square;
+mod solver;
+pub mod sudoku;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::problem::{Board, Problem};
+
+/// 제출된 풀이가 어긴 규칙 (작업 증명 모드에서는 nonce 가 풀이)
+///
+/// 라운드, 투표, 체인 규칙은 각각 `RoundError`, `VoteError`, `BlockError`로 나뉜다.
+/// 직렬화하면 `{"rule": "row_sum", "index": 1, ...}` 형태가 되어
+/// 클라이언트가 어떤 규칙에서 실패했는지 바로 알 수 있다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Error)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum SolutionError {
+    #[error("problem must be a non-empty square matrix")]
+    InvalidProblem,
+    #[error("solution must be a {expected}x{expected} matrix")]
+    InvalidShape { expected: usize },
+    #[error("cell ({row}, {col}) is given as {expected} but the solution has {actual}")]
+    GivenCellChanged { row: usize, col: usize, expected: u32, actual: u32 },
+    #[error("value {value} is outside the range 1..={max}")]
+    OutOfRange { value: u32, max: u32 },
+    #[error("value {value} is used more than once")]
+    DuplicateValue { value: u32 },
+    #[error("row {index} sums to {actual}, expected {expected}")]
+    RowSum { index: usize, expected: u32, actual: u32 },
+    #[error("column {index} sums to {actual}, expected {expected}")]
+    ColumnSum { index: usize, expected: u32, actual: u32 },
+    #[error("main diagonal sums to {actual}, expected {expected}")]
+    DiagonalSum { expected: u32, actual: u32 },
+    #[error("anti-diagonal sums to {actual}, expected {expected}")]
+    AntiDiagonalSum { expected: u32, actual: u32 },
+    #[error("value {value} appears more than once in row {index}")]
+    RepeatedInRow { index: usize, value: u32 },
+    #[error("value {value} appears more than once in column {index}")]
+    RepeatedInColumn { index: usize, value: u32 },
+    #[error("value {value} appears more than once in box {index}")]
+    RepeatedInBox { index: usize, value: u32 },
+    #[error("cage {index} does not reach {target}{op}")]
+    CageMismatch { index: usize, target: u32, op: String },
+    #[error("block targets {actual} leading zero bits, the round requires {expected}")]
+    TargetMismatch { expected: u32, actual: u32 },
+    #[error("header hash {hash} has {actual} leading zero bits, the target is {target}")]
+    InsufficientWork { hash: String, target: u32, actual: u32 },
+}
+
+/// 풀이 수를 셀 때 탐색할 최대 노드 수 (큰 판에서 생성이 멈추지 않도록)
+pub const SEARCH_BUDGET: usize = 200_000;
+
+/// 여러 풀이 모드에서 기록할 최대 풀이 수
+pub const MAX_COUNTED_SOLUTIONS: usize = 100;
+
+/// 문제 생성 조건
+#[derive(Debug, Clone, Copy)]
+pub struct GenerateParams {
+    /// 난이도 (비울 칸 수)
+    pub difficulty: u32,
+    /// 판 크기는 `min_size..=max_size` 중 퍼즐이 허용하는 크기에서 고름
+    pub min_size: usize,
+    pub max_size: usize,
+    /// `false`면 풀이가 여러 개인 문제도 허용 (풀이 수는 `Board::solution_count`에 기록)
+    pub unique: bool,
+}
+
+/// 서버가 낼 수 있는 퍼즐 한 종류
+///
+/// 새 퍼즐을 추가하려면 `Problem`에 변형을 하나 추가하고 이 트레이트를 구현한 뒤
+/// `PuzzleKind`에 등록하면 된다.
+pub trait Puzzle: Send + Sync {
+    /// 난이도(빈 칸 수)에 맞는 문제 생성
+    fn generate(&self, rng: &mut dyn RngCore, params: &GenerateParams) -> Problem;
+
+    /// 풀이가 문제의 규칙을 모두 만족하는지 검사
+    fn verify(&self, problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError>;
+
+    /// 사람이 읽을 수 있는 문제 설명 (로그, 안내 문구용)
+    fn describe(&self, problem: &Problem) -> String;
+
+    /// 빈 칸을 채우는 풀이 수를 `limit`개까지 셈
+    /// 탐색이 `SEARCH_BUDGET`을 넘으면 `None`
+    fn count_solutions(&self, problem: &Problem, limit: usize) -> Option<usize>;
+}
+
+/// 설정에서 고를 수 있는 퍼즐 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PuzzleKind {
+    MagicSquare,
+    LatinSquare,
+    Sudoku,
+    KenKen,
+}
+
+impl PuzzleKind {
+    pub fn puzzle(self) -> &'static dyn Puzzle {
+        match self {
+            PuzzleKind::MagicSquare => &magic_square::MagicSquarePuzzle,
+            PuzzleKind::LatinSquare => &latin_square::LatinSquarePuzzle,
+            PuzzleKind::Sudoku => &sudoku::SudokuPuzzle,
+            PuzzleKind::KenKen => &kenken::KenKenPuzzle,
+        }
+    }
+}
+
+impl FromStr for PuzzleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "magic_square" => Ok(PuzzleKind::MagicSquare),
+            "latin_square" => Ok(PuzzleKind::LatinSquare),
+            "sudoku" => Ok(PuzzleKind::Sudoku),
+            "kenken" => Ok(PuzzleKind::KenKen),
+            other => Err(format!("unknown puzzle kind: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PuzzleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PuzzleKind::MagicSquare => "magic_square",
+            PuzzleKind::LatinSquare => "latin_square",
+            PuzzleKind::Sudoku => "sudoku",
+            PuzzleKind::KenKen => "kenken",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 문제 종류에 맞는 퍼즐로 풀이 검증 (작업 증명 문제는 `pow::verify_work`로 검사)
+pub fn verify(problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+    match problem.kind() {
+        Some(kind) => kind.puzzle().verify(problem, solution),
+        None => Err(SolutionError::InvalidProblem),
+    }
+}
+
+// =============== 퍼즐 공통 검사 ===============
+
+/// 문제 판과 풀이가 같은 n x n 모양인지 확인하고 n 을 돌려줌
+pub(crate) fn check_shape(board: &Board, solution: &[Vec<u32>]) -> Result<usize, SolutionError> {
+    let n = board.matrix.len();
+    if n == 0 || n != board.size || board.matrix.iter().any(|row| row.len() != n) {
+        return Err(SolutionError::InvalidProblem);
+    }
+    if solution.len() != n || solution.iter().any(|row| row.len() != n) {
+        return Err(SolutionError::InvalidShape { expected: n });
+    }
+    Ok(n)
+}
+
+/// 문제에 주어진 숫자(0이 아닌 칸)를 그대로 유지하는지
+pub(crate) fn check_givens(givens: &[Vec<u32>], solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+    for (row, (given_row, solved_row)) in givens.iter().zip(solution).enumerate() {
+        for (col, (&expected, &actual)) in given_row.iter().zip(solved_row).enumerate() {
+            if expected != 0 && expected != actual {
+                return Err(SolutionError::GivenCellChanged { row, col, expected, actual });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 모든 칸이 1..=max 범위인지
+pub(crate) fn check_range(solution: &[Vec<u32>], max: u32) -> Result<(), SolutionError> {
+    match solution.iter().flatten().find(|&&value| value == 0 || value > max) {
+        Some(&value) => Err(SolutionError::OutOfRange { value, max }),
+        None => Ok(()),
+    }
+}
+
+/// 각 행과 열에 같은 숫자가 두 번 나오지 않는지 (라틴 방진 조건)
+pub(crate) fn check_latin(solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+    let n = solution.len();
+    for index in 0..n {
+        if let Some(value) = first_repeat(solution[index].iter().copied(), n) {
+            return Err(SolutionError::RepeatedInRow { index, value });
+        }
+        if let Some(value) = first_repeat(solution.iter().map(|row| row[index]), n) {
+            return Err(SolutionError::RepeatedInColumn { index, value });
+        }
+    }
+    Ok(())
+}
+
+/// 1..=max 범위의 값 중 처음으로 반복되는 값
+pub(crate) fn first_repeat(values: impl Iterator<Item = u32>, max: usize) -> Option<u32> {
+    let mut seen = vec![false; max + 1];
+    values.into_iter().find(|&value| {
+        let slot = &mut seen[(value as usize).min(max)];
+        std::mem::replace(slot, true)
+    })
+}
+
+/// 완성된 판에서 칸을 비우고 풀이 수를 `solution_count`에 기록
+///
+/// `unique`면 칸을 하나 비울 때마다 풀이를 세어, 풀이가 하나로 유지될 때만 비운다.
+/// 그래서 비운 칸 수가 `num_blank`보다 적을 수 있다.
+pub(crate) fn carve(
+    puzzle: &dyn Puzzle,
+    rng: &mut dyn RngCore,
+    mut problem: Problem,
+    num_blank: usize,
+    unique: bool,
+) -> Problem {
+    let positions = shuffled_cells(rng, problem.board().size);
+
+    if !unique {
+        for &(i, j) in positions.iter().take(num_blank) {
+            problem.board_mut().matrix[i][j] = 0;
+        }
+        let count = puzzle.count_solutions(&problem, MAX_COUNTED_SOLUTIONS);
+        problem.board_mut().solution_count = count.map(|count| count as u32);
+        return problem;
+    }
+
+    let mut blanked = 0;
+    for (i, j) in positions {
+        if blanked == num_blank {
+            break;
+        }
+        let value = std::mem::take(&mut problem.board_mut().matrix[i][j]);
+        if puzzle.count_solutions(&problem, 2) == Some(1) {
+            blanked += 1;
+        } else {
+            problem.board_mut().matrix[i][j] = value;
+        }
+    }
+    problem.board_mut().solution_count = Some(1);
+    problem
+}
+
+fn shuffled_cells(rng: &mut dyn RngCore, n: usize) -> Vec<(usize, usize)> {
+    use rand::seq::SliceRandom;
+
+    let mut positions: Vec<(usize, usize)> = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).collect();
+    positions.shuffle(rng);
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const ALL_KINDS: [PuzzleKind; 4] =
+        [PuzzleKind::MagicSquare, PuzzleKind::LatinSquare, PuzzleKind::Sudoku, PuzzleKind::KenKen];
+
+    fn empty_board(n: usize) -> Board {
+        Board { size: n, matrix: vec![vec![0; n]; n], difficulty: 0, solution_count: None }
+    }
+
+    proptest! {
+        #[test]
+        fn random_latin_square_is_valid(seed in any::<u64>(), n in 2usize..=9) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let square = latin_square::random_latin_square(&mut rng, n);
+            let problem = Problem::LatinSquare(empty_board(n));
+            prop_assert_eq!(verify(&problem, &square), Ok(()));
+        }
+
+        #[test]
+        fn random_sudoku_is_valid(seed in any::<u64>(), n in prop::sample::select(vec![4usize, 6, 8, 9, 10, 12])) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let grid = sudoku::random_sudoku(&mut rng, n);
+            let problem = Problem::Sudoku(empty_board(n));
+            prop_assert_eq!(verify(&problem, &grid), Ok(()));
+        }
+
+        #[test]
+        fn generated_problems_keep_their_size_and_blank_count(
+            seed in any::<u64>(),
+            difficulty in 1u32..=20,
+            kind in prop::sample::select(ALL_KINDS.to_vec()),
+            unique in any::<bool>(),
+        ) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let params = GenerateParams { difficulty, min_size: 4, max_size: 8, unique };
+            let problem = kind.puzzle().generate(&mut rng, &params);
+            let board = problem.board();
+            prop_assert_eq!(problem.kind(), Some(kind));
+            prop_assert_eq!(board.matrix.len(), board.size);
+            let blanks = board.matrix.iter().flatten().filter(|&&v| v == 0).count();
+            prop_assert!(blanks >= 1 && blanks <= difficulty as usize);
+        }
+
+        #[test]
+        fn unique_problems_have_exactly_one_solution(
+            seed in any::<u64>(),
+            difficulty in 1u32..=20,
+            kind in prop::sample::select(ALL_KINDS.to_vec()),
+        ) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let params = GenerateParams { difficulty, min_size: 4, max_size: 8, unique: true };
+            let problem = kind.puzzle().generate(&mut rng, &params);
+            prop_assert_eq!(problem.board().solution_count, Some(1));
+            prop_assert_eq!(kind.puzzle().count_solutions(&problem, 2), Some(1));
+        }
+    }
+}
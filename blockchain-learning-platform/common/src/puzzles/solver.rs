@@ -0,0 +1,193 @@
+// common/src/puzzles/solver.rs
+
+use super::kenken::evaluate;
+use crate::problem::{Cage, CageOp};
+
+/// 라틴 방진 계열 퍼즐(라틴 방진, 스도쿠, KenKen)의 빈 칸을 채우는 백트래킹 탐색
+///
+/// 같은 숫자가 두 번 나올 수 없는 칸 묶음(행, 열, 박스)마다 쓰인 숫자를 비트마스크로 두고,
+/// 매 단계마다 후보가 가장 적은 칸을 골라 채운다. 케이지는 칸을 채울 때마다 가지치기한다.
+struct LatinSearch<'a> {
+    n: usize,
+    grid: Vec<u32>,
+    /// 묶음마다 이미 쓰인 숫자 (비트 v = 숫자 v)
+    used: Vec<u64>,
+    /// 각 칸이 속한 묶음
+    cell_groups: Vec<Vec<usize>>,
+    cages: &'a [Cage],
+    cell_cage: Vec<Option<usize>>,
+    nodes_left: usize,
+    exhausted: bool,
+}
+
+impl<'a> LatinSearch<'a> {
+    /// `box_shape`(높이, 너비)가 있으면 박스 조건(스도쿠)도 적용
+    /// 주어진 숫자끼리 이미 규칙을 어기면 `None`
+    fn new(givens: &[Vec<u32>], box_shape: Option<(usize, usize)>, cages: &'a [Cage], budget: usize) -> Option<Self> {
+        let n = givens.len();
+        // 숫자 1..=n 을 u64 비트마스크에 담음
+        if n == 0 || n >= 64 || givens.iter().any(|row| row.len() != n) {
+            return None;
+        }
+
+        let mut cell_groups = vec![Vec::new(); n * n];
+        for i in 0..n {
+            for j in 0..n {
+                let groups = &mut cell_groups[i * n + j];
+                groups.push(i);
+                groups.push(n + j);
+                if let Some((height, width)) = box_shape {
+                    groups.push(2 * n + (i / height) * height + j / width);
+                }
+            }
+        }
+
+        let mut cell_cage = vec![None; n * n];
+        for (index, cage) in cages.iter().enumerate() {
+            for &(i, j) in &cage.cells {
+                if i >= n || j >= n {
+                    return None;
+                }
+                cell_cage[i * n + j] = Some(index);
+            }
+        }
+
+        let mut search = LatinSearch {
+            n,
+            grid: vec![0; n * n],
+            used: vec![0; 3 * n],
+            cell_groups,
+            cages,
+            cell_cage,
+            nodes_left: budget,
+            exhausted: false,
+        };
+
+        for (cell, &value) in givens.iter().flatten().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            if value as usize > n || search.candidates(cell) & (1 << value) == 0 {
+                return None;
+            }
+            search.place(cell, value);
+        }
+        // 주어진 숫자만으로 다 찬 케이지는 여기서 확인
+        let settled = cages.iter().all(|cage| {
+            let values: Vec<u32> = cage.cells.iter().map(|&(i, j)| search.grid[i * n + j]).collect();
+            values.contains(&0) || evaluate(cage.op, &values) == Some(cage.target)
+        });
+        settled.then_some(search)
+    }
+
+    /// 칸에 넣을 수 있는 숫자 (비트 v = 숫자 v)
+    fn candidates(&self, cell: usize) -> u64 {
+        let full = ((1u64 << self.n) - 1) << 1;
+        let used = self.cell_groups[cell].iter().fold(0, |acc, &group| acc | self.used[group]);
+        full & !used
+    }
+
+    /// 케이지를 아직 맞출 수 있는지 (다 찼으면 목표값과 같은지)
+    fn cage_allows(&self, cell: usize, value: u32) -> bool {
+        let Some(index) = self.cell_cage[cell] else {
+            return true;
+        };
+        let cage = &self.cages[index];
+        let mut values = Vec::with_capacity(cage.cells.len());
+        let mut empty = 0;
+        for &(i, j) in &cage.cells {
+            let other = i * self.n + j;
+            if other == cell {
+                values.push(value);
+            } else if self.grid[other] == 0 {
+                empty += 1;
+            } else {
+                values.push(self.grid[other]);
+            }
+        }
+
+        if empty == 0 {
+            return evaluate(cage.op, &values) == Some(cage.target);
+        }
+        match cage.op {
+            CageOp::Add => {
+                let sum: u32 = values.iter().sum();
+                sum + empty <= cage.target && sum + empty * self.n as u32 >= cage.target
+            }
+            CageOp::Mul => cage.target.is_multiple_of(values.iter().product::<u32>()),
+            _ => true,
+        }
+    }
+
+    fn place(&mut self, cell: usize, value: u32) {
+        self.grid[cell] = value;
+        for &group in &self.cell_groups[cell] {
+            self.used[group] |= 1 << value;
+        }
+    }
+
+    fn unplace(&mut self, cell: usize) {
+        let value = std::mem::take(&mut self.grid[cell]);
+        for &group in &self.cell_groups[cell] {
+            self.used[group] &= !(1 << value);
+        }
+    }
+
+    /// 해를 찾을 때마다 `found`를 늘리고, `limit`에 도달하거나 한도를 넘으면 `true` (중단)
+    fn run(&mut self, limit: usize, found: &mut usize) -> bool {
+        if self.nodes_left == 0 {
+            self.exhausted = true;
+            return true;
+        }
+        self.nodes_left -= 1;
+
+        // 후보가 가장 적은 빈 칸
+        let mut best: Option<(usize, u64)> = None;
+        for cell in (0..self.grid.len()).filter(|&cell| self.grid[cell] == 0) {
+            let mask = self.candidates(cell);
+            if mask == 0 {
+                return false;
+            }
+            if best.is_none_or(|(_, best_mask)| mask.count_ones() < best_mask.count_ones()) {
+                best = Some((cell, mask));
+                if mask.count_ones() == 1 {
+                    break;
+                }
+            }
+        }
+        let Some((cell, mask)) = best else {
+            *found += 1;
+            return *found >= limit;
+        };
+
+        for value in (1..=self.n as u32).filter(|&value| mask & (1 << value) != 0) {
+            if !self.cage_allows(cell, value) {
+                continue;
+            }
+            self.place(cell, value);
+            let stop = self.run(limit, found);
+            self.unplace(cell);
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// 빈 칸을 채우는 풀이 수를 `limit`개까지 셈
+/// 탐색 노드가 `budget`을 넘으면 `None`
+pub(crate) fn count_latin_solutions(
+    givens: &[Vec<u32>],
+    box_shape: Option<(usize, usize)>,
+    cages: &[Cage],
+    limit: usize,
+    budget: usize,
+) -> Option<usize> {
+    let Some(mut search) = LatinSearch::new(givens, box_shape, cages, budget) else {
+        return Some(0);
+    };
+    let mut found = 0;
+    search.run(limit, &mut found);
+    (!search.exhausted).then_some(found)
+}
@@ -0,0 +1,107 @@
+// common/src/puzzles/sudoku.rs
+
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use super::solver::count_latin_solutions;
+use super::{
+    carve, check_givens, check_latin, check_range, check_shape, first_repeat, GenerateParams, Puzzle, SolutionError,
+    SEARCH_BUDGET,
+};
+use crate::difficulty;
+use crate::problem::{Board, Problem};
+
+/// 스도쿠: 라틴 방진 조건에 더해 각 박스에도 1..=n 을 한 번씩 배치하는 퍼즐
+///
+/// n = 박스 높이 × 박스 너비 (예: 4 = 2x2, 6 = 2x3, 9 = 3x3)
+pub struct SudokuPuzzle;
+
+impl Puzzle for SudokuPuzzle {
+    fn generate(&self, rng: &mut dyn RngCore, params: &GenerateParams) -> Problem {
+        let (size, blanks) =
+            difficulty::layout(params.difficulty, params.min_size, params.max_size, |n| box_shape(n).is_some());
+        let matrix = random_sudoku(rng, size);
+        let board = Board { size, matrix, difficulty: params.difficulty, solution_count: None };
+        carve(self, rng, Problem::Sudoku(board), blanks, params.unique)
+    }
+
+    fn verify(&self, problem: &Problem, solution: &[Vec<u32>]) -> Result<(), SolutionError> {
+        let n = check_shape(problem.board(), solution)?;
+        let (box_height, box_width) = box_shape(n).ok_or(SolutionError::InvalidProblem)?;
+        check_givens(&problem.board().matrix, solution)?;
+        check_range(solution, n as u32)?;
+        check_latin(solution)?;
+
+        for index in 0..n {
+            let top = (index / box_height) * box_height;
+            let left = (index % box_height) * box_width;
+            let cells = (top..top + box_height).flat_map(|i| (left..left + box_width).map(move |j| solution[i][j]));
+            if let Some(value) = first_repeat(cells, n) {
+                return Err(SolutionError::RepeatedInBox { index, value });
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self, problem: &Problem) -> String {
+        let n = problem.board().size;
+        let (box_height, box_width) = box_shape(n).unwrap_or((n, 1));
+        format!("{n}x{n} sudoku: every row, column and {box_height}x{box_width} box holds 1..={n} exactly once")
+    }
+
+    fn count_solutions(&self, problem: &Problem, limit: usize) -> Option<usize> {
+        let board = problem.board();
+        let Some(shape) = box_shape(board.size) else {
+            return Some(0);
+        };
+        count_latin_solutions(&board.matrix, Some(shape), &[], limit, SEARCH_BUDGET)
+    }
+}
+
+/// n x n 스도쿠의 박스 모양 (높이, 너비)
+///
+/// 높이는 √n 이하의 가장 큰 약수. 1 x n 박스는 라틴 방진과 같으므로 허용하지 않음
+pub fn box_shape(n: usize) -> Option<(usize, usize)> {
+    (2..=n)
+        .take_while(|d| d * d <= n)
+        .filter(|&d| n.is_multiple_of(d))
+        .last()
+        .map(|height| (height, n / height))
+}
+
+/// 무작위로 완성된 n x n 스도쿠
+///
+/// 기본 패턴 (너비·(i mod 높이) + i/높이 + j) mod n 에서
+/// 밴드 안의 행, 밴드끼리, 스택 안의 열, 스택끼리, 숫자를 각각 섞는다.
+pub fn random_sudoku(rng: &mut dyn RngCore, n: usize) -> Vec<Vec<u32>> {
+    let (box_height, box_width) = box_shape(n).expect("sudoku size must have a box shape");
+
+    // 그룹(밴드/스택) 순서와 그룹 안의 순서를 섞은 인덱스
+    let mut shuffled = |groups: usize, group_len: usize| -> Vec<usize> {
+        let mut order: Vec<usize> = (0..groups).collect();
+        order.shuffle(rng);
+        order
+            .into_iter()
+            .flat_map(|group| {
+                let mut inner: Vec<usize> = (0..group_len).map(|k| group * group_len + k).collect();
+                inner.shuffle(rng);
+                inner
+            })
+            .collect()
+    };
+    // 가로 밴드는 box_width 개, 각 밴드는 box_height 행
+    let rows = shuffled(box_width, box_height);
+    // 세로 스택은 box_height 개, 각 스택은 box_width 열
+    let cols = shuffled(box_height, box_width);
+
+    let mut symbols: Vec<u32> = (1..=n as u32).collect();
+    symbols.shuffle(rng);
+
+    rows.iter()
+        .map(|&i| {
+            cols.iter()
+                .map(|&j| symbols[(box_width * (i % box_height) + i / box_height + j) % n])
+                .collect()
+        })
+        .collect()
+}
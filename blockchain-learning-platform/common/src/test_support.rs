@@ -0,0 +1,19 @@
+// common/src/test_support.rs
+
+//! 여러 크레이트의 테스트가 함께 쓰는 블록 (`test-support` 기능, 서버와 클라이언트는 dev-dependency 로 켬)
+
+use crate::body::{BlockBody, Transaction, BLOCK_REWARD};
+use crate::chain::GENESIS_TIMESTAMP;
+use crate::problem::Problem;
+use crate::protocol::Block;
+
+/// 원장 규칙만 보는 테스트용 블록: `index` 높이에 `body`를 담고 문제와 풀이는 비어 있음
+/// (부모 해시는 비어 있고 시각은 제네시스 시각)
+pub fn block_with_body(index: u64, miner: &str, body: BlockBody) -> Block {
+    Block::new(index, String::new(), GENESIS_TIMESTAMP, Problem::empty(4), vec![], vec![], miner.to_string(), body)
+}
+
+/// `miner`가 `BLOCK_REWARD`를 받고 `transactions`를 담은 블록
+pub fn block(index: u64, miner: &str, transactions: Vec<Transaction>) -> Block {
+    block_with_body(index, miner, BlockBody { transactions, ..BlockBody::reward(miner, BLOCK_REWARD) })
+}
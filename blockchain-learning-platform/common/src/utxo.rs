@@ -0,0 +1,381 @@
+// common/src/utxo.rs
+
+//! UTXO 원장: 거래는 보내는 쪽이 가진 쓰지 않은 출력(입력)을 모두 쓰고 새 출력을 만든다.
+//!
+//! 거래 하나가 만드는 출력은 최대 두 개다. 0번은 받는 쪽에게 `amount`, 1번은 입력 합에서 남은 거스름돈을 보내는 쪽에게.
+//! 코인베이스는 블록마다 출력 하나를 만든다.
+//! 쓴 출력은 다시 쓸 수 없으므로 계정 원장과 달리 nonce 로 순서를 매기지 않는다 (체인 아이디는 검사).
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::body::{Coinbase, Transaction, TransactionError};
+use crate::chain::{BlockError, DEFAULT_CHAIN_ID};
+use crate::header::Encoder;
+use crate::ledger::LedgerModel;
+use crate::protocol::Block;
+
+/// 어떤 거래의 몇 번째 출력인지
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct OutPoint {
+    pub txid: String, // 출력을 만든 거래의 해시 (hex)
+    pub vout: u32,    // 그 거래의 출력 번호
+}
+
+impl OutPoint {
+    /// 높이 `height` 블록의 코인베이스 출력
+    /// 같은 노드가 같은 보상을 여러 번 받아도 겹치지 않도록 높이를 넣어 해시
+    pub fn coinbase(height: u64, coinbase: &Coinbase) -> Self {
+        let mut encoder = Encoder::leaf("coinbase_output");
+        encoder.u64(height);
+        encoder.str(&coinbase.receiver_id);
+        encoder.u64(coinbase.amount);
+        OutPoint { txid: hex::encode(encoder.finish()), vout: 0 }
+    }
+}
+
+impl fmt::Display for OutPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.txid, self.vout)
+    }
+}
+
+/// 쓰지 않은 출력 하나
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxOut {
+    pub owner: String,
+    pub amount: u64,
+}
+
+/// 블록 하나를 반영하며 UTXO 집합에서 빠진 출력과 새로 생긴 출력 (저장소를 조금씩 고칠 때 씀)
+///
+/// 같은 블록에서 생겼다가 바로 쓰인 출력은 어느 쪽에도 없다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UtxoDiff {
+    pub spent: Vec<OutPoint>,
+    pub created: Vec<(OutPoint, TxOut)>,
+}
+
+impl UtxoDiff {
+    fn spend(&mut self, outpoint: OutPoint) {
+        match self.created.iter().position(|(created, _)| *created == outpoint) {
+            Some(position) => {
+                self.created.remove(position);
+            }
+            None => self.spent.push(outpoint),
+        }
+    }
+
+    fn merge(&mut self, other: UtxoDiff) {
+        for outpoint in other.spent {
+            self.spend(outpoint);
+        }
+        self.created.extend(other.created);
+    }
+}
+
+/// 블록의 보상과 거래를 제네시스부터 차례로 반영한 쓰지 않은 출력 전체
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoSet {
+    pub height: u64,   // 마지막으로 반영한 블록 높이
+    pub chain_id: u32, // 이 체인의 거래만 반영
+    outputs: BTreeMap<OutPoint, TxOut>,
+}
+
+impl Default for UtxoSet {
+    fn default() -> Self {
+        UtxoSet::new(DEFAULT_CHAIN_ID)
+    }
+}
+
+impl UtxoSet {
+    /// 아무 블록도 반영하지 않은 `chain_id` 체인의 출력 집합
+    pub fn new(chain_id: u32) -> Self {
+        UtxoSet { height: 0, chain_id, outputs: BTreeMap::new() }
+    }
+
+    /// 저장해 둔 출력으로 다시 만듦
+    pub fn from_outputs(height: u64, chain_id: u32, outputs: impl IntoIterator<Item = (OutPoint, TxOut)>) -> Self {
+        UtxoSet { height, chain_id, outputs: outputs.into_iter().collect() }
+    }
+
+    /// 블록들을 높이 순서대로 반영 (제네시스는 건너뜀)
+    pub fn replay<'a>(chain_id: u32, blocks: impl IntoIterator<Item = &'a Block>) -> Result<Self, (u64, BlockError)> {
+        let mut set = UtxoSet::new(chain_id);
+        for block in blocks.into_iter().filter(|block| block.index > 0) {
+            set.apply_block(block).map_err(|e| (block.index, e))?;
+        }
+        Ok(set)
+    }
+
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&TxOut> {
+        self.outputs.get(outpoint)
+    }
+
+    /// 쓰지 않은 출력 전체 (출력 순)
+    pub fn iter(&self) -> impl Iterator<Item = (&OutPoint, &TxOut)> {
+        self.outputs.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// `owner`가 가진 쓰지 않은 출력
+    pub fn unspent<'a>(&'a self, owner: &'a str) -> impl Iterator<Item = (&'a OutPoint, &'a TxOut)> + 'a {
+        self.outputs.iter().filter(move |(_, output)| output.owner == owner)
+    }
+
+    /// `owner`가 가진 쓰지 않은 출력의 합
+    pub fn balance(&self, owner: &str) -> u64 {
+        self.unspent(owner).map(|(_, output)| output.amount).sum()
+    }
+
+    /// `amount`(보낼 금액과 수수료의 합)를 내는 데 쓸 출력을 큰 것부터 고름 (`reserved`는 대기 중인 거래가 이미 쓴 출력)
+    pub fn select_coins(
+        &self,
+        owner: &str,
+        amount: u64,
+        reserved: &HashSet<OutPoint>,
+    ) -> Result<Vec<OutPoint>, TransactionError> {
+        let mut candidates: Vec<(&OutPoint, &TxOut)> =
+            self.unspent(owner).filter(|(outpoint, _)| !reserved.contains(*outpoint)).collect();
+        candidates.sort_by(|a, b| b.1.amount.cmp(&a.1.amount).then_with(|| a.0.cmp(b.0)));
+
+        let mut selected = Vec::new();
+        let mut total: u64 = 0;
+        for (outpoint, output) in &candidates {
+            if total >= amount {
+                break;
+            }
+            selected.push((*outpoint).clone());
+            total = total.saturating_add(output.amount);
+        }
+        if total < amount {
+            return Err(TransactionError::InsufficientFunds { account: owner.to_string(), balance: total, amount });
+        }
+        Ok(selected)
+    }
+
+    /// 체인 끝 다음 블록을 반영하고 바뀐 출력을 돌려줌 (실패하면 아무것도 바꾸지 않음)
+    pub fn apply_block(&mut self, block: &Block) -> Result<UtxoDiff, BlockError> {
+        let expected = self.height + 1;
+        if block.index != expected {
+            return Err(BlockError::WrongHeight { expected, actual: block.index });
+        }
+        let mut next = self.clone();
+        let mut diff = UtxoDiff::default();
+
+        // 코인베이스를 먼저 만들어 같은 블록의 거래가 쓸 수 있음
+        let coinbase = &block.body.coinbase;
+        if coinbase.amount > 0 {
+            let output = TxOut { owner: coinbase.receiver_id.clone(), amount: coinbase.amount };
+            next.create(&mut diff, OutPoint::coinbase(block.index, coinbase), output);
+        }
+
+        let mut spent_in_block = HashSet::new();
+        for (index, transaction) in block.body.transactions.iter().enumerate() {
+            let invalid = |error| BlockError::InvalidTransaction { index, error };
+            if let Some(outpoint) = transaction.inputs.iter().find(|input| spent_in_block.contains(*input)) {
+                return Err(invalid(TransactionError::DoubleSpend { outpoint: outpoint.clone() }));
+            }
+            diff.merge(next.apply_transaction(transaction).map_err(invalid)?);
+            spent_in_block.extend(transaction.inputs.iter().cloned());
+        }
+
+        next.height = block.index;
+        *self = next;
+        Ok(diff)
+    }
+
+    /// 블록을 반영할 수 있는지만 확인
+    pub fn check_block(&self, block: &Block) -> Result<(), BlockError> {
+        self.clone().apply_block(block).map(|_| ())
+    }
+
+    /// 거래 하나를 반영: 입력이 모두 보내는 쪽의 쓰지 않은 출력이고 합이 `amount`와 `fee`의 합 이상이어야 함
+    /// 남은 금액에서 수수료를 뺀 만큼이 거스름돈 (수수료는 블록을 만든 노드가 코인베이스로 받음)
+    /// 실패하면 아무것도 바꾸지 않음
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> Result<UtxoDiff, TransactionError> {
+        transaction.validate()?;
+        if transaction.chain_id != self.chain_id {
+            return Err(TransactionError::WrongChain { expected: self.chain_id, actual: transaction.chain_id });
+        }
+        if transaction.inputs.is_empty() {
+            return Err(TransactionError::WrongLedger { ledger: LedgerModel::Utxo });
+        }
+
+        let mut seen = HashSet::new();
+        let mut total: u64 = 0;
+        for input in &transaction.inputs {
+            if !seen.insert(input) {
+                return Err(TransactionError::DoubleSpend { outpoint: input.clone() });
+            }
+            let output = self
+                .outputs
+                .get(input)
+                .ok_or_else(|| TransactionError::InputNotUnspent { outpoint: input.clone() })?;
+            if output.owner != transaction.sender_id {
+                return Err(TransactionError::NotOwner { outpoint: input.clone(), owner: output.owner.clone() });
+            }
+            total = total.saturating_add(output.amount);
+        }
+        let cost = transaction.cost();
+        if total < cost {
+            return Err(TransactionError::InsufficientFunds {
+                account: transaction.sender_id.clone(),
+                balance: total,
+                amount: cost,
+            });
+        }
+
+        let mut diff = UtxoDiff::default();
+        for input in &transaction.inputs {
+            self.outputs.remove(input);
+            diff.spend(input.clone());
+        }
+        let txid = hex::encode(transaction.hash());
+        let payment = TxOut { owner: transaction.receiver_id.clone(), amount: transaction.amount };
+        self.create(&mut diff, OutPoint { txid: txid.clone(), vout: 0 }, payment);
+        let change = total - cost;
+        if change > 0 {
+            let output = TxOut { owner: transaction.sender_id.clone(), amount: change };
+            self.create(&mut diff, OutPoint { txid, vout: 1 }, output);
+        }
+        Ok(diff)
+    }
+
+    fn create(&mut self, diff: &mut UtxoDiff, outpoint: OutPoint, output: TxOut) {
+        self.outputs.insert(outpoint.clone(), output.clone());
+        diff.created.push((outpoint, output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::{BlockBody, BLOCK_REWARD};
+    use crate::test_support::block;
+
+    fn spend(sender: &str, receiver: &str, amount: u64, inputs: Vec<OutPoint>) -> Transaction {
+        Transaction { inputs, ..Transaction::new(sender, receiver, amount) }
+    }
+
+    fn reward(height: u64, miner: &str) -> OutPoint {
+        OutPoint::coinbase(height, &BlockBody::reward(miner, BLOCK_REWARD).coinbase)
+    }
+
+    #[test]
+    fn spending_creates_payment_and_change_outputs() {
+        let payment = spend("alice", "bob", 4, vec![reward(1, "alice")]);
+        let chain = [Block::genesis(), block(1, "alice", vec![]), block(2, "bob", vec![payment.clone()])];
+        let set = UtxoSet::replay(DEFAULT_CHAIN_ID, &chain).unwrap();
+
+        assert_eq!(set.height, 2);
+        assert_eq!(set.balance("alice"), 6);
+        assert_eq!(set.balance("bob"), 14);
+        assert_eq!(set.get(&reward(1, "alice")), None);
+        let txid = hex::encode(payment.hash());
+        assert_eq!(set.get(&OutPoint { txid: txid.clone(), vout: 0 }), Some(&TxOut { owner: "bob".to_string(), amount: 4 }));
+        assert_eq!(set.get(&OutPoint { txid, vout: 1 }), Some(&TxOut { owner: "alice".to_string(), amount: 6 }));
+
+        // 같은 노드가 같은 보상을 받아도 높이가 다르면 다른 출력
+        assert_ne!(reward(1, "alice"), reward(3, "alice"));
+    }
+
+    #[test]
+    fn fees_come_out_of_the_change() {
+        let mut set = UtxoSet::replay(DEFAULT_CHAIN_ID, &[Block::genesis(), block(1, "alice", vec![])]).unwrap();
+        let payment = Transaction { fee: 2, ..spend("alice", "bob", 4, vec![reward(1, "alice")]) };
+        let mut mined = block(2, "carol", vec![]);
+        mined.body = BlockBody::with_fees("carol", BLOCK_REWARD, vec![payment.clone()]);
+        set.apply_block(&mined).unwrap();
+
+        assert_eq!(set.balance("alice"), 4);
+        assert_eq!(set.balance("bob"), 4);
+        assert_eq!(set.balance("carol"), BLOCK_REWARD + 2);
+        let change = OutPoint { txid: hex::encode(payment.hash()), vout: 1 };
+        assert_eq!(set.get(&change), Some(&TxOut { owner: "alice".to_string(), amount: 4 }));
+
+        // 입력이 금액과 수수료를 함께 덮어야 함
+        let short = Transaction { fee: 1, ..spend("alice", "bob", 4, vec![change.clone()]) };
+        assert_eq!(
+            set.apply_transaction(&short),
+            Err(TransactionError::InsufficientFunds { account: "alice".to_string(), balance: 4, amount: 5 })
+        );
+    }
+
+    #[test]
+    fn double_spends_and_foreign_inputs_reject_the_block() {
+        let mut set = UtxoSet::replay(DEFAULT_CHAIN_ID, &[Block::genesis(), block(1, "alice", vec![])]).unwrap();
+        let before = set.clone();
+
+        let twice = block(
+            2,
+            "bob",
+            vec![spend("alice", "bob", 1, vec![reward(1, "alice")]), spend("alice", "carol", 1, vec![reward(1, "alice")])],
+        );
+        assert_eq!(
+            set.apply_block(&twice),
+            Err(BlockError::InvalidTransaction { index: 1, error: TransactionError::DoubleSpend { outpoint: reward(1, "alice") } })
+        );
+
+        let stolen = block(2, "bob", vec![spend("bob", "carol", 1, vec![reward(1, "alice")])]);
+        assert_eq!(
+            set.apply_block(&stolen),
+            Err(BlockError::InvalidTransaction {
+                index: 0,
+                error: TransactionError::NotOwner { outpoint: reward(1, "alice"), owner: "alice".to_string() },
+            })
+        );
+
+        let account_style = block(2, "bob", vec![Transaction::new("alice", "bob", 1)]);
+        assert_eq!(
+            set.apply_block(&account_style),
+            Err(BlockError::InvalidTransaction { index: 0, error: TransactionError::WrongLedger { ledger: LedgerModel::Utxo } })
+        );
+        assert_eq!(set, before);
+
+        // 이미 쓴 출력은 다음 블록에서 다시 쓸 수 없음
+        set.apply_block(&block(2, "bob", vec![spend("alice", "bob", 10, vec![reward(1, "alice")])])).unwrap();
+        assert_eq!(
+            set.check_block(&block(3, "bob", vec![spend("alice", "bob", 10, vec![reward(1, "alice")])])),
+            Err(BlockError::InvalidTransaction { index: 0, error: TransactionError::InputNotUnspent { outpoint: reward(1, "alice") } })
+        );
+    }
+
+    #[test]
+    fn diffs_skip_outputs_spent_in_the_same_block() {
+        let mut set = UtxoSet::default();
+        // 코인베이스를 같은 블록에서 바로 씀
+        let diff = set.apply_block(&block(1, "alice", vec![spend("alice", "bob", 10, vec![reward(1, "alice")])])).unwrap();
+        assert!(diff.spent.is_empty());
+        assert_eq!(diff.created.len(), 1);
+        assert_eq!(UtxoSet::from_outputs(1, DEFAULT_CHAIN_ID, diff.created), set);
+    }
+
+    #[test]
+    fn coin_selection_prefers_large_outputs_and_skips_reserved_ones() {
+        let chain = [Block::genesis(), block(1, "alice", vec![]), block(2, "alice", vec![]), block(3, "alice", vec![])];
+        let mut set = UtxoSet::replay(DEFAULT_CHAIN_ID, &chain).unwrap();
+        // 3번 보상을 나눠 7 짜리 거스름돈을 만듦
+        set.apply_block(&block(4, "bob", vec![spend("alice", "bob", 3, vec![reward(3, "alice")])])).unwrap();
+        assert_eq!(set.balance("alice"), 27);
+
+        let none = HashSet::new();
+        assert_eq!(set.select_coins("alice", 10, &none).unwrap().len(), 1);
+        assert_eq!(set.select_coins("alice", 21, &none).unwrap().len(), 3);
+
+        let reserved: HashSet<OutPoint> = [reward(1, "alice")].into_iter().collect();
+        assert_eq!(
+            set.select_coins("alice", 20, &reserved),
+            Err(TransactionError::InsufficientFunds { account: "alice".to_string(), balance: 17, amount: 20 })
+        );
+    }
+}
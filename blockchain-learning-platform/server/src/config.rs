@@ -0,0 +1,194 @@
+// server/src/config.rs
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use blockchain_common::chain::{IssueRules, DEFAULT_CHAIN_ID, DEFAULT_MAX_BLOCK_TRANSACTIONS, DEFAULT_MAX_CLOCK_SKEW_MS};
+use blockchain_common::emission::Emission;
+use blockchain_common::ledger::LedgerModel;
+use blockchain_common::puzzles::PuzzleKind;
+
+/// 서버 설정 (환경 변수로 덮어쓸 수 있음)
+///
+/// - `CONSENSUS_MODE`    : `puzzle`(기본) 또는 `pow` (해시 작업 증명)
+/// - `POW_TARGET_BITS`   : 작업 증명 모드에서 헤더 해시 앞자리 0 비트 수 (기본 16)
+/// - `PUZZLE_KINDS`      : 낼 퍼즐 종류, 쉼표로 구분 (기본 `magic_square`).
+///   `magic_square`, `latin_square`, `sudoku`, `kenken` 중에서 라운드마다 무작위로 고름
+/// - `PUZZLE_SIZE`       : 판 한 변의 최소 길이 (기본 4)
+/// - `MAX_PUZZLE_SIZE`   : 난이도가 올라갈 때 키울 수 있는 최대 길이 (기본 8)
+/// - `PUZZLE_BLANKS`     : 처음 문제에서 비울 칸 수, 즉 초기 난이도 (기본 4)
+/// - `TARGET_BLOCK_SECS` : 목표 블록 간격(초) (기본 30)
+/// - `RETARGET_INTERVAL` : 난이도를 다시 계산하는 라운드 주기 (기본 5)
+/// - `ROUND_TIMEOUT_SECS`: 이 시간(초) 안에 합의가 없으면 라운드를 만료시키고 새로 냄 (기본 120)
+/// - `MAX_CLOCK_SKEW_SECS`: 블록 시각이 서버 시계보다 앞서도 되는 시간(초) (기본 60)
+/// - `MEMPOOL_CAPACITY`  : 멤풀에 담아 둘 수 있는 최대 거래 수 (기본 1000)
+/// - `MAX_BLOCK_TRANSACTIONS`: 블록 용량, 블록 하나에 담을 수 있는 최대 거래 수 (기본 100).
+///   템플릿은 수수료가 큰 거래부터 채우고, 넘는 블록은 서버와 검증 노드 모두 거절
+/// - `LEDGER_MODEL`      : `account`(기본, 계정 잔액) 또는 `utxo` (쓰지 않은 출력). 체인을 처음 시작할 때 정함
+/// - `CHAIN_ID`          : 거래에 넣어야 하는 체인 아이디 (기본 1). 다른 체인의 거래를 다시 쓰지 못하게 서버마다 다르게 둠
+/// - `EMISSION`          : 블록 보상 규칙 (기본 `fixed:10`). `fixed:<보상>`, `halving:<처음 보상>:<간격>`,
+///   `capped:<보상>:<총 발행 한도>` 중 하나. 체인을 처음 시작할 때 정함
+/// - `ALLOW_MULTIPLE_SOLUTIONS` : `true`면 풀이가 여러 개인 문제도 냄 (기본 `false`, 풀이가 하나뿐인 문제만)
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub mode: ConsensusMode,
+    pub pow_target_bits: u32,
+    pub puzzle_kinds: Vec<PuzzleKind>,
+    pub puzzle_size: usize,
+    pub max_puzzle_size: usize,
+    pub blank_count: usize,
+    pub target_block_time: Duration,
+    pub retarget_interval: usize,
+    pub round_timeout: Duration,
+    pub max_clock_skew: Duration,
+    pub mempool_capacity: usize,
+    pub max_block_transactions: usize,
+    pub ledger: LedgerModel,
+    pub chain_id: u32,
+    pub emission: Emission,
+    pub allow_multiple_solutions: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            mode: ConsensusMode::Puzzle,
+            pow_target_bits: 16,
+            puzzle_kinds: vec![PuzzleKind::MagicSquare],
+            puzzle_size: 4,
+            max_puzzle_size: 8,
+            blank_count: 4,
+            target_block_time: Duration::from_secs(30),
+            retarget_interval: 5,
+            round_timeout: Duration::from_secs(120),
+            max_clock_skew: Duration::from_millis(DEFAULT_MAX_CLOCK_SKEW_MS),
+            mempool_capacity: 1000,
+            max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
+            ledger: LedgerModel::Account,
+            chain_id: DEFAULT_CHAIN_ID,
+            emission: Emission::default(),
+            allow_multiple_solutions: false,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Self {
+        let default = ServerConfig::default();
+        let mut config = ServerConfig {
+            mode: read_env("CONSENSUS_MODE", default.mode),
+            pow_target_bits: read_env("POW_TARGET_BITS", default.pow_target_bits),
+            puzzle_kinds: read_puzzle_kinds(default.puzzle_kinds.clone()),
+            puzzle_size: read_env("PUZZLE_SIZE", default.puzzle_size),
+            max_puzzle_size: read_env("MAX_PUZZLE_SIZE", default.max_puzzle_size),
+            blank_count: read_env("PUZZLE_BLANKS", default.blank_count),
+            target_block_time: Duration::from_secs(read_env(
+                "TARGET_BLOCK_SECS",
+                default.target_block_time.as_secs(),
+            )),
+            retarget_interval: read_env("RETARGET_INTERVAL", default.retarget_interval),
+            round_timeout: Duration::from_secs(read_env(
+                "ROUND_TIMEOUT_SECS",
+                default.round_timeout.as_secs(),
+            )),
+            max_clock_skew: Duration::from_secs(read_env(
+                "MAX_CLOCK_SKEW_SECS",
+                default.max_clock_skew.as_secs(),
+            )),
+            mempool_capacity: read_env("MEMPOOL_CAPACITY", default.mempool_capacity),
+            max_block_transactions: read_env("MAX_BLOCK_TRANSACTIONS", default.max_block_transactions),
+            ledger: read_env("LEDGER_MODEL", default.ledger),
+            chain_id: read_env("CHAIN_ID", default.chain_id),
+            emission: read_env("EMISSION", default.emission),
+            allow_multiple_solutions: read_env("ALLOW_MULTIPLE_SOLUTIONS", default.allow_multiple_solutions),
+        };
+
+        if config.puzzle_size == 0 {
+            eprintln!("PUZZLE_SIZE must be positive, falling back to {}", default.puzzle_size);
+            config.puzzle_size = default.puzzle_size;
+        }
+        if config.round_timeout.is_zero() {
+            eprintln!("ROUND_TIMEOUT_SECS must be positive, falling back to {}", default.round_timeout.as_secs());
+            config.round_timeout = default.round_timeout;
+        }
+        config.max_puzzle_size = config.max_puzzle_size.max(config.puzzle_size);
+        // sha256 해시는 256비트
+        config.pow_target_bits = config.pow_target_bits.min(256);
+        config
+    }
+
+    /// 문제를 내는 규칙 (라운드에 담아 검증 노드가 블록의 문제를 다시 만들어 볼 수 있게 함)
+    pub fn issue_rules(&self) -> IssueRules {
+        IssueRules {
+            kinds: self.puzzle_kinds.clone(),
+            min_size: self.puzzle_size,
+            max_size: self.max_puzzle_size,
+            unique: !self.allow_multiple_solutions,
+            work_target: match self.mode {
+                ConsensusMode::Puzzle => None,
+                ConsensusMode::ProofOfWork => Some(self.pow_target_bits),
+            },
+        }
+    }
+}
+
+/// 블록을 만들 권리를 얻는 방법
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsensusMode {
+    /// 퍼즐 풀이 제출 (기본)
+    Puzzle,
+    /// nonce 를 바꿔 가며 블록 헤더 해시가 목표를 만족할 때까지 계산
+    ProofOfWork,
+}
+
+impl FromStr for ConsensusMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "puzzle" => Ok(ConsensusMode::Puzzle),
+            "pow" | "proof_of_work" => Ok(ConsensusMode::ProofOfWork),
+            other => Err(format!("unknown consensus mode: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for ConsensusMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConsensusMode::Puzzle => "puzzle",
+            ConsensusMode::ProofOfWork => "pow",
+        };
+        f.write_str(name)
+    }
+}
+
+/// `PUZZLE_KINDS` 를 읽음, 모르는 이름은 건너뛰고 하나도 없으면 기본값 사용
+fn read_puzzle_kinds(default: Vec<PuzzleKind>) -> Vec<PuzzleKind> {
+    let Ok(raw) = env::var("PUZZLE_KINDS") else {
+        return default;
+    };
+    let kinds: Vec<PuzzleKind> = raw
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .filter_map(|name| name.parse().map_err(|e| eprintln!("{}", e)).ok())
+        .collect();
+    if kinds.is_empty() {
+        eprintln!("PUZZLE_KINDS={:?} names no puzzle, using defaults", raw);
+        return default;
+    }
+    kinds
+}
+
+/// 환경 변수를 읽어 파싱, 없거나 잘못된 값이면 기본값 사용
+fn read_env<T: FromStr + fmt::Display>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            eprintln!("Invalid {}={:?}, using default {}", key, raw, default);
+            default
+        }),
+        Err(_) => default,
+    }
+}
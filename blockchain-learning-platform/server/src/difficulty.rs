@@ -0,0 +1,100 @@
+// server/src/difficulty.rs
+
+use std::time::Duration;
+
+/// 비트코인처럼 한 번에 조정되는 폭은 1/4 ~ 4배로 제한
+const MAX_ADJUST_FACTOR: u32 = 4;
+
+/// 라운드 풀이 시간에 맞춰 난이도를 조정하는 구조체
+///
+/// 난이도는 "비워 둘 칸 수"이며, 칸 수가 현재 크기에 비해 너무 많아지면
+/// `blockchain_common::difficulty::layout`에서 판 크기를 키운다.
+/// `interval` 라운드마다 실제 걸린 시간과 목표 시간(`target_block_time` × 라운드 수)을
+/// 비교해 난이도를 다시 계산한다.
+#[derive(Debug)]
+pub struct DifficultyAdjuster {
+    current: u32,
+    target_block_time: Duration,
+    interval: usize,
+    window: Vec<Duration>,
+}
+
+impl DifficultyAdjuster {
+    pub fn new(initial: u32, target_block_time: Duration, interval: usize) -> Self {
+        DifficultyAdjuster {
+            current: initial.max(1),
+            target_block_time,
+            interval: interval.max(1),
+            window: Vec::new(),
+        }
+    }
+
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// 한 라운드(문제 브로드캐스트 ~ 합의)에 걸린 시간을 기록
+    /// 조정 주기에 도달하면 새 난이도를 돌려줌
+    pub fn record_round(&mut self, elapsed: Duration) -> Option<u32> {
+        self.window.push(elapsed);
+        if self.window.len() < self.interval {
+            return None;
+        }
+
+        let actual: Duration = self.window.drain(..).sum();
+        let expected = self.target_block_time * self.interval as u32;
+        self.current = retarget(self.current, actual, expected);
+        Some(self.current)
+    }
+}
+
+/// 새 난이도 = 현재 난이도 × 목표 시간 / 실제 시간 (1/4 ~ 4배로 제한, 최소 1)
+pub fn retarget(current: u32, actual: Duration, expected: Duration) -> u32 {
+    let actual = actual.as_secs_f64().max(f64::EPSILON);
+    let ratio = (expected.as_secs_f64() / actual)
+        .clamp(1.0 / MAX_ADJUST_FACTOR as f64, MAX_ADJUST_FACTOR as f64);
+    ((current as f64 * ratio).round() as u32).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn retarget_follows_the_time_ratio() {
+        assert_eq!(retarget(10, 20 * SECOND, 10 * SECOND), 5);
+        assert_eq!(retarget(10, 5 * SECOND, 10 * SECOND), 20);
+        assert_eq!(retarget(10, 10 * SECOND, 10 * SECOND), 10);
+    }
+
+    #[test]
+    fn retarget_is_clamped_to_a_quarter_and_four_times() {
+        assert_eq!(retarget(8, 1000 * SECOND, 10 * SECOND), 2);
+        assert_eq!(retarget(8, Duration::ZERO, 10 * SECOND), 32);
+    }
+
+    #[test]
+    fn difficulty_never_drops_below_one() {
+        assert_eq!(retarget(1, 1000 * SECOND, 10 * SECOND), 1);
+        assert_eq!(retarget(2, 1000 * SECOND, 10 * SECOND), 1);
+        assert_eq!(DifficultyAdjuster::new(0, 10 * SECOND, 1).current(), 1);
+    }
+
+    #[test]
+    fn retargets_once_per_interval() {
+        let mut adjuster = DifficultyAdjuster::new(10, 10 * SECOND, 3);
+        assert_eq!(adjuster.record_round(5 * SECOND), None);
+        assert_eq!(adjuster.record_round(5 * SECOND), None);
+        assert_eq!(adjuster.current(), 10);
+        // 세 라운드에 15초 (목표 30초) → 두 배
+        assert_eq!(adjuster.record_round(5 * SECOND), Some(20));
+        assert_eq!(adjuster.current(), 20);
+
+        // 주기가 끝나면 창을 비우고 다시 셈
+        assert_eq!(adjuster.record_round(40 * SECOND), None);
+        assert_eq!(adjuster.record_round(40 * SECOND), None);
+        assert_eq!(adjuster.record_round(40 * SECOND), Some(5));
+    }
+}
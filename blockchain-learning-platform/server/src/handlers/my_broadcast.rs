@@ -2,80 +2,39 @@
 
 use axum::{
     extract::ws::{Message as WsMessage, WebSocket},
-    extract::{Extension, Json},
-    response::IntoResponse,
+    extract::{Extension, Json, Path},
+    response::{IntoResponse, Response},
     http::StatusCode,
 };
 use std::sync::Arc;
 use tokio::sync::{broadcast::Sender as BroadcastSender, broadcast::Receiver as BroadcastReceiver, mpsc::Sender as MpscSender, Mutex};
+use serde::Serialize;
 use serde_json::json;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-
-use crate::models::{self, Block, Problem, ServerMessage, Transaction, ValidationResult};
+use blockchain_common::body::TransactionError;
+use blockchain_common::ledger::Ledger;
+use blockchain_common::chain::{self, BlockError, ChainTip};
+use blockchain_common::identity;
+use blockchain_common::puzzles;
+
+use crate::config::{ConsensusMode, ServerConfig};
+use crate::difficulty::DifficultyAdjuster;
+use crate::mempool::Mempool;
+use crate::models::{self, Block, Problem, Round, RoundError, ServerMessage, Transaction, ValidationResult, VoteError};
+use crate::pow;
 use std::collections::HashMap;
-use std::time::Duration;
-
-/// 4x4 마방진 생성
-fn generate_random_magic_square() -> Vec<Vec<u32>> {
-    let base_magic_square = vec![
-        vec![16, 2, 3, 13],
-        vec![5, 11, 10, 8],
-        vec![9, 7, 6, 12],
-        vec![4, 14, 15, 1],
-    ];
-
-    let mut rng = thread_rng();
-
-    // 행을 랜덤하게 섞음
-    let mut rows = base_magic_square.clone();
-    rows.shuffle(&mut rng);
-
-    // 열을 랜덤하게 섞음
-    let mut cols: Vec<Vec<u32>> = vec![vec![0; 4]; 4];
-    let col_order: Vec<usize> = (0..4).collect::<Vec<_>>().choose_multiple(&mut rng, 4).cloned().collect();
-
-    for i in 0..4 {
-        for j in 0..4 {
-            cols[i][j] = rows[i][col_order[j]];
-        }
-    }
-
-    cols
-}
-
-/// 특정 개수의 값을 0으로 비우는 마방진 생성
-fn generate_incomplete_magic_square(num_blank: usize) -> Vec<Vec<u32>> {
-    let mut magic_square = generate_random_magic_square();
-    let mut rng = thread_rng();
-
-    // 4x4 매트릭스의 인덱스를 모두 수집
-    let mut positions: Vec<(usize, usize)> = (0..4).flat_map(|i| (0..4).map(move |j| (i, j))).collect();
-
-    // 비울 인덱스를 랜덤하게 선택
-    positions.shuffle(&mut rng);
-    let blank_positions = &positions[..num_blank.min(16)];
-
-    // 값 비우기: 비워진 위치를 0으로 설정
-    for &(i, j) in blank_positions {
-        magic_square[i][j] = 0;
-    }
-
-    magic_square
-}
+use std::fmt::Display;
+use std::time::{Duration, Instant};
 
 // =============== 문제 브로드캐스트 ===============
 pub async fn broadcast_problem(
-    Extension(tx): Extension<Arc<BroadcastSender<Problem>>>,
+    Extension(tx): Extension<Arc<BroadcastSender<Round>>>,
+    Extension(server): Extension<Arc<Mutex<Server>>>,
 ){
-    // 랜덤 마방진 생성 및 값 비우기
-    let matrix = generate_incomplete_magic_square(4); // 4개의 빈 칸 생성
-
-    // Problem 생성
-    let problem = Problem { matrix };
+    // 현재 난이도에 맞는 랜덤 마방진 생성 및 값 비우기 (라운드 시작)
+    let round = server.lock().await.start_round();
 
     // 문제 브로드캐스트
-    match tx.send(problem.clone()) {
+    match tx.send(round) {
         Ok(subscriber_count) => {
             println!(
                 "Problem broadcasted successfully to {} subscribers.",
@@ -93,15 +52,72 @@ pub async fn handle_block_submission(
     Json(block): Json<Block>,
     Extension(tx): Extension<Arc<BroadcastSender<String>>>, 
     Extension(server): Extension<Arc<Mutex<Server>>>,
-) -> impl IntoResponse {
+) -> Response {
     println!("Received block in handle_block_submission: {:?}", block);
 
+    // ================
+    // 0) 풀이 검증: 잘못된 풀이는 current_block 이 될 수 없음
+    //    지금 진행 중인 라운드에서 낸 바로 그 문제인지, `node_id` 의 키로 서명했는지, 거래마다 보내는 쪽이 서명했는지,
+    //    코인베이스가 블록을 낸 노드에게 발행 규칙대로의 보상을 주는지, 거래 수가 블록 용량 이하인지,
+    //    체인 끝 다음 높이의 문제인지, 난이도가 조정기의 현재 난이도인지,
+    //    블록 시각이 부모 이후이고 서버 시계보다 너무 앞서지 않는지 먼저 확인하고,
+    //    작업 증명 모드에서는 헤더 해시가 목표를 만족하는지 검사
+    // ================
+    let (work_target, difficulty, emission, max_transactions, tip, tip_timestamp, max_skew_ms, issued) = {
+        let guard = server.lock().await;
+        (
+            guard.work_target(),
+            guard.expected_difficulty(),
+            guard.config.emission,
+            guard.config.max_block_transactions,
+            guard.tip,
+            guard.tip_timestamp,
+            guard.config.max_clock_skew.as_millis() as u64,
+            guard.issued_problem(block.round_id),
+        )
+    };
+    let issued = match issued {
+        Ok(problem) => problem,
+        Err(e) => {
+            println!("Rejected block from {}: {}", block.node_id, e);
+            return rejection(StatusCode::CONFLICT, e);
+        }
+    };
+    let verdict = identity::verify_block(&block.header(), &block.signature)
+        .map_err(BlockError::from)
+        .and_then(|()| identity::verify_transactions(&block.body))
+        .and_then(|()| emission.check_coinbase(&block))
+        .and_then(|()| chain::check_capacity(&block.body, max_transactions))
+        .and_then(|()| check_chain_position(&block, &tip, &issued))
+        .and_then(|()| chain::check_difficulty(block.difficulty, &block.problem, difficulty))
+        .and_then(|()| chain::check_timestamp(block.timestamp, tip_timestamp, chain::now_millis(), max_skew_ms))
+        .and_then(|()| match work_target {
+            Some(target_bits) => pow::verify_work(&block, target_bits).map_err(BlockError::from),
+            None => puzzles::verify(&block.problem, &block.solution).map_err(BlockError::from),
+        });
+    if let Err(e) = verdict {
+        println!("Rejected block from {}: {}", block.node_id, e);
+        return rejection(StatusCode::UNPROCESSABLE_ENTITY, e);
+    }
+
     {
         // ================
         // 1) 서버 잠금
         // ================
         let mut guard = server.lock().await;
 
+        // 검증하는 사이 합의가 끝나거나 라운드가 만료되었을 수 있음
+        if let Err(e) = guard.check_round(block.round_id) {
+            println!("Rejected block from {}: {}", block.node_id, e);
+            return rejection(StatusCode::CONFLICT, e);
+        }
+
+        // 보상과 거래를 지금 원장에 반영할 수 있어야 함 (잔액보다 많이 보내거나 이미 쓴 출력을 쓰는 거래가 있으면 거절)
+        if let Err(e) = guard.ledger.check_block(&block) {
+            println!("Rejected block from {}: {}", block.node_id, e);
+            return rejection(StatusCode::UNPROCESSABLE_ENTITY, e);
+        }
+
         // ================
         // 2) 이미 블록이 있나?
         // ================
@@ -109,7 +125,7 @@ pub async fn handle_block_submission(
             // *에러 발생시키지 않음*
             // 대신 "이미 제출됨" 이라는 문구와 함께 200 OK
             println!("A block was already submitted. Ignoring new block.");
-            return (StatusCode::OK, "Block already submitted. Ignoring new block.");
+            return (StatusCode::OK, "Block already submitted. Ignoring new block.").into_response();
         }
 
         // ================
@@ -127,28 +143,318 @@ pub async fn handle_block_submission(
     if let Err(e) = tx.send(serialized_message) {
         eprintln!("Failed to broadcast block: {}", e);
         // "절대 오류를 일으키지 마라" → 상태코드 200 + 로그만 출력
-        return (StatusCode::OK, "Failed to broadcast block, but no error raised.");
+        return (StatusCode::OK, "Failed to broadcast block, but no error raised.").into_response();
     }
 
-    (StatusCode::OK, "Block submitted and broadcasted successfully")
+    (StatusCode::OK, "Block submitted and broadcasted successfully").into_response()
+}
+
+// =============== 검증 결과(투표) 제출 ===============
+pub async fn handle_validation_submission(
+    Json(validation_result): Json<ValidationResult>,
+    Extension(validation_sender): Extension<MpscSender<ValidationResult>>,
+    Extension(server): Extension<Arc<Mutex<Server>>>,
+) -> Response {
+    // 지난 라운드 블록에 대한 투표, 서명이 없거나 다른 블록을 겨냥한 투표는 합의에 넣지 않음
+    let (round_check, vote_check) = {
+        let guard = server.lock().await;
+        (guard.check_round(validation_result.round_id), guard.check_vote(&validation_result))
+    };
+    if let Err(e) = round_check {
+        println!("Rejected vote from {}: {}", validation_result.node_id, e);
+        return rejection(StatusCode::CONFLICT, e);
+    }
+    if let Err(e) = vote_check {
+        println!("Rejected vote from {}: {}", validation_result.node_id, e);
+        return rejection(StatusCode::UNPROCESSABLE_ENTITY, e);
+    }
+
+    if let Err(e) = validation_sender.send(validation_result).await {
+        eprintln!("Failed to send validation result: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to submit validation result").into_response();
+    }
+    (StatusCode::OK, "Validation result submitted successfully").into_response()
+}
+
+// =============== 거래 제출 ===============
+pub async fn handle_transaction_submission(
+    Json(transaction): Json<Transaction>,
+    Extension(tx): Extension<Arc<BroadcastSender<String>>>,
+    Extension(server): Extension<Arc<Mutex<Server>>>,
+) -> Response {
+    let mut guard = server.lock().await;
+    let server = &mut *guard;
+    // 보내는 쪽의 키로 서명한 거래만 멤풀에 넣음 (다른 사람의 아이디로 보낼 수 없음)
+    let accepted = identity::verify_transaction(&transaction)
+        .and_then(|()| server.mempool.insert(transaction.clone(), &server.ledger));
+    if let Err(e) = accepted {
+        println!("Rejected transaction {:?}: {}", transaction, e);
+        let status = match e {
+            TransactionError::Duplicate { .. } => StatusCode::CONFLICT,
+            TransactionError::MempoolFull { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        return rejection(status, e);
+    }
+    println!("Transaction added to the mempool ({} pending): {:?}", server.mempool.len(), transaction);
+    server.broadcast_mempool(&tx);
+    (StatusCode::OK, "Transaction added to the mempool").into_response()
+}
+
+// =============== 잔액 조회 ===============
+/// 합의된 체인을 재생한 잔액과 다음 거래에 쓸 nonce:
+/// `{"node_id": ..., "balance": ..., "next_nonce": ..., "height": ..., "ledger": "account" | "utxo", "chain_id": ...}`
+pub async fn handle_balance(
+    Path(node_id): Path<String>,
+    Extension(server): Extension<Arc<Mutex<Server>>>,
+) -> Response {
+    let guard = server.lock().await;
+    let body = json!({
+        "node_id": node_id,
+        "balance": guard.ledger.balance(&node_id),
+        "next_nonce": guard.ledger.next_nonce(&node_id),
+        "height": guard.ledger.height(),
+        "ledger": guard.ledger.model(),
+        "chain_id": guard.ledger.chain_id(),
+    });
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+// =============== 발행량 조회 ===============
+/// 합의된 체인 끝까지 코인베이스로 발행된 코인과 발행 규칙:
+/// `{"height": ..., "total_supply": ..., "next_reward": ..., "next_halving_height": ... | null, "emission": {"schedule": ..., ...}}`
+pub async fn handle_supply(Extension(server): Extension<Arc<Mutex<Server>>>) -> Response {
+    let guard = server.lock().await;
+    let emission = guard.config.emission;
+    let height = guard.ledger.height();
+    let body = json!({
+        "height": height,
+        "total_supply": emission.supply(height),
+        "next_reward": emission.reward(height + 1),
+        "next_halving_height": emission.next_halving(height),
+        "emission": emission,
+    });
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// 거절 사유를 `{"status": "rejected", "reason": {...}, "message": "..."}` 본문으로 응답
+fn rejection<E: Serialize + Display>(status: StatusCode, e: E) -> Response {
+    let body = json!({
+        "status": "rejected",
+        "reason": e,
+        "message": e.to_string(),
+    });
+    (status, Json(body)).into_response()
+}
+
+/// 블록이 체인 끝 바로 다음 높이에서 체인 끝을 부모로 가리키고,
+/// 머클 루트가 본문과, 적힌 해시가 헤더와 맞으며, 이 라운드에서 낸 문제(`issued`)를 그대로 담았는지
+///
+/// 문제는 블록이 적어 온 종류, 크기, 난이도로 다시 만들지 않고 라운드의 문제와 비교한다.
+/// (그렇지 않으면 같은 시드로 만든 더 쉬운 문제를 풀어 와도 통과한다.)
+fn check_chain_position(block: &Block, tip: &ChainTip, issued: &Problem) -> Result<(), BlockError> {
+    let height = tip.next_height();
+    if block.index != height {
+        return Err(BlockError::WrongHeight { expected: height, actual: block.index });
+    }
+    let tip_hash = tip.hash_hex();
+    if block.prev_hash != tip_hash {
+        return Err(BlockError::PrevHashMismatch { expected: tip_hash, actual: block.prev_hash.clone() });
+    }
+    block.check_merkle_root()?;
+    let hash = hex::encode(block.compute_hash());
+    if block.hash != hash {
+        return Err(BlockError::HashMismatch { expected: hash, actual: block.hash.clone() });
+    }
+    if block.problem != *issued {
+        return Err(BlockError::ProblemNotIssued { height });
+    }
+    Ok(())
 }
 
 // =============== 서버(합의/거래 흐름) 구조체 ===============
 pub struct Server {
     current_block: Option<Block>,
     votes: HashMap<String /* node_id */, bool>,
-    total_nodes: usize,
     is_problem_solved: bool, // 문제 해결 상태 추가
+    config: ServerConfig,
+    difficulty: DifficultyAdjuster,
+    round_started_at: Instant, // 마지막으로 문제를 브로드캐스트한 시각
+    tip: ChainTip,             // 마지막으로 합의된 블록 (다음 문제의 시드)
+    tip_timestamp: u64,        // 마지막으로 합의된 블록의 시각 (다음 블록은 이보다 이르면 안 됨)
+    round: Option<Round>,      // 지금 진행 중인 라운드 (첫 브로드캐스트 전에는 `None`)
+    last_round_id: u64,        // 마지막으로 낸 라운드 번호
+    expired_round: Option<u64>, // 마지막으로 마감 시각을 넘겨 만료된 라운드
+    mempool: Mempool,          // 아직 블록에 들어가지 않은 거래
+    ledger: Ledger,            // 합의된 블록까지 반영한 원장 (계정 잔액 또는 UTXO)
 }
 
 impl Server {
     /// `validation_sender`를 외부에서 전달받아 사용하도록 수정
-    pub fn new(total_nodes: usize, _validation_sender: MpscSender<ValidationResult>) -> Self {
+    pub fn new(config: ServerConfig, _validation_sender: MpscSender<ValidationResult>) -> Self {
         Server {
             current_block: None,
             votes: HashMap::new(),
-            total_nodes,
             is_problem_solved: false, // 초기 상태 설정
+            difficulty: DifficultyAdjuster::new(
+                config.blank_count as u32,
+                config.target_block_time,
+                config.retarget_interval,
+            ),
+            round_started_at: Instant::now(),
+            tip: ChainTip::genesis(),
+            tip_timestamp: chain::GENESIS_TIMESTAMP,
+            round: None,
+            last_round_id: 0,
+            expired_round: None,
+            mempool: Mempool::new(config.mempool_capacity),
+            ledger: Ledger::new(config.ledger, config.chain_id),
+            config,
+        }
+    }
+
+    /// 작업 증명 모드면 헤더 해시의 목표 비트 수
+    pub fn work_target(&self) -> Option<u32> {
+        match self.config.mode {
+            ConsensusMode::Puzzle => None,
+            ConsensusMode::ProofOfWork => Some(self.config.pow_target_bits),
+        }
+    }
+
+    /// 지금 라운드의 블록이 가져야 할 난이도 (퍼즐 모드는 조정기의 현재 난이도, 작업 증명 모드는 목표 비트 수)
+    pub fn expected_difficulty(&self) -> u32 {
+        self.work_target().unwrap_or_else(|| self.difficulty.current())
+    }
+
+    /// 지금 진행 중인 라운드 번호 (라운드가 없으면 0)
+    pub fn active_round_id(&self) -> u64 {
+        self.round.as_ref().map_or(0, |round| round.id)
+    }
+
+    /// 제출이 지금 진행 중인 라운드를 겨냥했는지
+    pub fn check_round(&self, submitted: u64) -> Result<(), RoundError> {
+        let active = self.active_round_id();
+        if active != 0 && submitted == active {
+            return Ok(());
+        }
+        if self.expired_round == Some(submitted) {
+            return Err(RoundError::RoundExpired { round: submitted });
+        }
+        Err(RoundError::StaleRound { active, submitted })
+    }
+
+    /// 제출이 지금 진행 중인 라운드를 겨냥했으면 그 라운드에서 낸 문제
+    pub fn issued_problem(&self, submitted: u64) -> Result<Problem, RoundError> {
+        self.check_round(submitted)?;
+        let round = self.round.as_ref().ok_or(RoundError::StaleRound { active: 0, submitted })?;
+        Ok(round.problem.clone())
+    }
+
+    /// 마감 시각(`round_timeout`)이 지나도록 합의가 없으면 라운드를 만료시킴
+    /// 대기 중인 블록과 투표를 버리고, 같은 높이에서 새 라운드를 시작해 돌려줌
+    /// (문제는 체인 끝으로 시드를 정하므로 난이도가 바뀌지 않았다면 같은 문제가 다시 나옴)
+    pub fn expire_round_if_due(&mut self) -> Option<Round> {
+        let round_id = self.round.as_ref()?.id;
+        let elapsed = self.round_started_at.elapsed();
+        if elapsed < self.config.round_timeout {
+            return None;
+        }
+
+        println!(
+            "Round {} expired after {:.1}s without a commit{}",
+            round_id,
+            elapsed.as_secs_f64(),
+            if self.current_block.is_some() { ", dropping the pending block" } else { "" }
+        );
+        self.current_block = None;
+        self.votes.clear();
+        self.is_problem_solved = false;
+        self.expired_round = Some(round_id);
+        // 아무도 못 푼 라운드도 느린 라운드로 기록해 난이도가 내려가게 함
+        if self.work_target().is_none() {
+            self.record_round_time(elapsed);
+        }
+        Some(self.start_round())
+    }
+
+    /// 멤풀에 대기 중인 거래 전체를 WebSocket 으로 알림
+    pub fn broadcast_mempool(&self, tx: &BroadcastSender<String>) {
+        let message = ServerMessage::Mempool(self.mempool.pending());
+        let serialized_message = serde_json::to_string(&message).unwrap();
+        if let Err(e) = tx.send(serialized_message) {
+            eprintln!("Failed to broadcast mempool: {}", e);
+        }
+    }
+
+    /// 새 라운드 번호를 매기고 문제를 만들어 현재 라운드로 기록
+    pub fn start_round(&mut self) -> Round {
+        self.round_started_at = Instant::now();
+        self.last_round_id += 1;
+        let round = Round {
+            id: self.last_round_id,
+            height: self.tip.next_height(),
+            problem: self.next_problem(),
+            // 블록 템플릿: 멤풀 앞쪽에서 지금 원장에 반영할 수 있는 거래
+            transactions: self.mempool.template(self.config.max_block_transactions, &self.ledger),
+            ledger: self.config.ledger,
+            chain_id: self.config.chain_id,
+            emission: self.config.emission,
+            rules: self.config.issue_rules(),
+            max_transactions: self.config.max_block_transactions,
+        };
+        self.round = Some(round.clone());
+        round
+    }
+
+    /// 다음 높이의 문제 생성
+    /// 퍼즐 모드는 설정된 퍼즐 종류 중 하나를 골라 현재 난이도로 생성
+    /// (난수는 체인 끝의 해시와 다음 높이로 시드를 정하므로 클라이언트가 같은 규칙으로 다시 만들어 볼 수 있음)
+    fn next_problem(&self) -> Problem {
+        let height = self.tip.next_height();
+        let problem = self.config.issue_rules().issue(&self.tip, self.difficulty.current());
+        let Some(kind) = problem.kind() else {
+            println!(
+                "Round {} at height {}: proof of work, find a nonce whose header hash has {} leading zero bits",
+                self.last_round_id,
+                height,
+                problem.board().difficulty
+            );
+            return problem;
+        };
+
+        let solutions = match problem.board().solution_count {
+            Some(count) => count.to_string(),
+            None => "unknown".to_string(),
+        };
+        println!(
+            "Round {} at height {}: {} ({} solution(s))",
+            self.last_round_id,
+            height,
+            kind.puzzle().describe(&problem),
+            solutions
+        );
+        problem
+    }
+
+    /// 문제 브로드캐스트부터 합의까지 걸린 시간을 기록하고 필요하면 난이도 재조정
+    fn finish_round(&mut self) {
+        let elapsed = self.round_started_at.elapsed();
+        if let Some(target_bits) = self.work_target() {
+            println!("Round mined in {:.1}s (target {} bits)", elapsed.as_secs_f64(), target_bits);
+            return;
+        }
+        println!("Round solved in {:.1}s (difficulty {})", elapsed.as_secs_f64(), self.difficulty.current());
+        self.record_round_time(elapsed);
+    }
+
+    /// 라운드에 걸린 시간을 난이도 조정기에 넘기고, 재조정되면 로그를 남김
+    fn record_round_time(&mut self, elapsed: Duration) {
+        if let Some(new_difficulty) = self.difficulty.record_round(elapsed) {
+            println!(
+                "Difficulty retargeted to {} (target block time {}s)",
+                new_difficulty,
+                self.config.target_block_time.as_secs()
+            );
         }
     }
 
@@ -159,54 +465,113 @@ impl Server {
         self.is_problem_solved = false; // 새 문제이므로 상태 초기화
     }
 
+    /// 투표가 서명되었고, 대기 중인 블록을 겨냥했으며, 이 노드의 첫 투표인지
+    pub fn check_vote(&self, vote: &ValidationResult) -> Result<(), VoteError> {
+        identity::verify_vote(&vote.node_id, vote.round_id, &vote.block_hash, vote.is_valid, &vote.signature)?;
+        let block = self.current_block.as_ref().ok_or(VoteError::NoPendingBlock)?;
+        if vote.block_hash != block.hash {
+            return Err(VoteError::VoteForOtherBlock {
+                expected: block.hash.clone(),
+                actual: vote.block_hash.clone(),
+            });
+        }
+        if self.votes.contains_key(&vote.node_id) {
+            return Err(VoteError::DuplicateVote { node_id: vote.node_id.clone() });
+        }
+        Ok(())
+    }
+
     pub fn add_vote(&mut self, node_id: String, is_valid: bool) {
         self.votes.insert(node_id, is_valid);
     }
 
-    // pub fn check_consensus(&self) -> bool {
-    //     let valid_count = self.votes.values().filter(|&&v| v).count();
-    //     valid_count > self.total_nodes / 2
-    // }
-
+    /// 찬성 투표가 하나라도 있으면 합의
     pub fn check_consensus(&self) -> bool {
         self.votes.values().any(|&v| v)
     }
-    // pub fn check_consensus(&self) -> bool {
-    //     let valid_count = self.votes.values().filter(|&&v| v).count();
-    //     valid_count >= 2 // 두 개 이상의 true 투표가 있을 경우
-    // }
 
     /// 문제 해결 상태 업데이트
     pub fn mark_problem_as_solved(&mut self) {
         self.is_problem_solved = true;
     }
 
-    /// 문제 해결 여부 확인
-    pub fn problem_solved(&self) -> bool {
-        self.is_problem_solved
-    }
-
     /// 다수결 검증 로직 처리
     /// 합의 달성 시 과반수 결과를 출력
     pub async fn process_consensus(
         &mut self, 
         validation_result: ValidationResult,
-        problem_tx: Arc<BroadcastSender<Problem>>, // 두 번째 인자 추가
+        problem_tx: Arc<BroadcastSender<Round>>, // 두 번째 인자 추가
+        block_tx: Arc<BroadcastSender<String>>,  // 멤풀 변경 알림용
     ) {
+        // 0) 큐에 있는 사이 라운드가 끝났거나, 서명이 없거나, 중복이거나, 다른 블록에 대한 투표면 버림
+        if let Err(e) = self
+            .check_round(validation_result.round_id)
+            .map_err(VoteError::from)
+            .and_then(|()| self.check_vote(&validation_result))
+        {
+            println!("Dropped vote from {}: {}", validation_result.node_id, e);
+            return;
+        }
+
         // 1) 투표 기록
         self.add_vote(validation_result.node_id, validation_result.is_valid);
         
         // 2) 다수결 체크
         if self.check_consensus() && !self.is_problem_solved {
             println!("Consensus reached with at least one valid vote.");
+
+            // 합의된 블록을 원장에 먼저 반영: 반영할 수 없는 블록은 체인에 붙이지 않고 버림
+            // (체인 끝과 시각은 그대로 두고, 아래에서 같은 높이의 라운드를 다시 냄)
+            let committed = match self.current_block.take() {
+                Some(block) => match self.ledger.apply_block(&block) {
+                    Ok(()) => Some(block),
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to apply block {} to the {} ledger, dropping it: {}",
+                            block.index,
+                            self.ledger.model(),
+                            e
+                        );
+                        self.votes.clear();
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            // 합의된 블록이 새 체인 끝 (다음 문제의 시드)
+            if let Some(block) = committed {
+                // 문제 해결 상태 업데이트
+                self.mark_problem_as_solved();
+                self.finish_round();
+
+                self.tip = ChainTip { height: block.index, hash: block.compute_hash() };
+                self.tip_timestamp = block.timestamp;
+                println!(
+                    "Block {} rewarded {} with {} (total supply {})",
+                    block.index,
+                    block.body.coinbase.receiver_id,
+                    block.body.coinbase.amount,
+                    self.config.emission.supply(block.index)
+                );
+
+                // 블록에 들어간 거래와, 새 원장에는 반영할 수 없게 된 거래는 멤풀에서 뺌
+                let removed = self.mempool.remove_included(&block.body.transactions);
+                let dropped = self.mempool.prune(&self.ledger);
+                if removed > 0 {
+                    println!("Removed {} committed transaction(s) from the mempool", removed);
+                }
+                if dropped > 0 {
+                    println!("Dropped {} unaffordable transaction(s) from the mempool", dropped);
+                }
+                if removed + dropped > 0 {
+                    self.broadcast_mempool(&block_tx);
+                }
+            }
     
-            // 문제 해결 상태 업데이트
-            self.mark_problem_as_solved();
-    
-            // 새 문제 브로드캐스트
-            let new_matrix = generate_incomplete_magic_square(4);
-            let new_problem = Problem { matrix: new_matrix };
-            if let Err(e) = problem_tx.send(new_problem.clone()) {
+            // 새 문제 브로드캐스트 (블록을 버렸으면 같은 높이에서 다시)
+            let new_round = self.start_round();
+            if let Err(e) = problem_tx.send(new_round) {
                 eprintln!("Failed to broadcast new problem after consensus: {}", e);
             } else {
                 println!("New problem broadcasted after consensus.");
@@ -225,7 +590,7 @@ impl Server {
 // WebSocket 핸들러 함수
 pub async fn handle_websocket(
     ws: axum::extract::ws::WebSocketUpgrade,
-    problem_tx: Arc<BroadcastSender<Problem>>,
+    problem_tx: Arc<BroadcastSender<Round>>,
     block_tx: Arc<BroadcastSender<String>>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| handle_socket(socket, problem_tx, block_tx))
@@ -233,20 +598,21 @@ pub async fn handle_websocket(
 
 async fn handle_socket(
     mut socket: WebSocket,
-    problem_tx: Arc<BroadcastSender<Problem>>,
+    problem_tx: Arc<BroadcastSender<Round>>,
     block_tx: Arc<BroadcastSender<String>>,
 ) {
     // 각 채널의 수신기 생성
-    let mut problem_rx: BroadcastReceiver<Problem> = problem_tx.subscribe();
+    let mut problem_rx: BroadcastReceiver<Round> = problem_tx.subscribe();
     let mut block_rx: BroadcastReceiver<String> = block_tx.subscribe();
 
     loop {
         tokio::select! {
             // 문제 채널에서 새로운 메시지가 도착한 경우
-            Ok(problem) = problem_rx.recv() => {
+            Ok(round) = problem_rx.recv() => {
                 let msg = json!({
                     "type": "problem",
-                    "data": problem
+                    "data": round,
+                    "version": models::PROTOCOL_VERSION
                 });
                 if let Err(e) = socket.send(WsMessage::Text(msg.to_string())).await {
                     eprintln!("WebSocket send error: {}", e);
@@ -254,14 +620,15 @@ async fn handle_socket(
                 }
             }
 
-            // 블록 채널에서 새로운 메시지가 도착한 경우
+            // 블록 채널에서 새로운 메시지(블록, 멤풀 변경)가 도착한 경우
             Ok(block) = block_rx.recv() => {
                 let block_json: serde_json::Value = serde_json::from_str(&block).expect("Failed to parse block");
 
                 println!("block_json: {}", block_json);
                 let msg = json!({
-                    "type": "block",
-                    "data": block_json["data"] // 중첩 없이 JSON 객체로 포함
+                    "type": block_json["type"],
+                    "data": block_json["data"], // 중첩 없이 JSON 객체로 포함
+                    "version": models::PROTOCOL_VERSION
                 });
                 if let Err(e) = socket.send(WsMessage::Text(msg.to_string())).await {
                     eprintln!("WebSocket send error: {}", e);
@@ -278,3 +645,78 @@ async fn handle_socket(
 
     println!("WebSocket connection closed");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockchain_common::chain::IssueRules;
+    use blockchain_common::protocol::{BlockBody, BLOCK_REWARD};
+    use blockchain_common::puzzles::PuzzleKind;
+    use tokio::sync::{broadcast, mpsc};
+
+    fn block_for(problem: Problem, tip: &ChainTip) -> Block {
+        Block::new(
+            tip.next_height(),
+            tip.hash_hex(),
+            chain::GENESIS_TIMESTAMP,
+            problem,
+            vec![],
+            vec![],
+            "miner".to_string(),
+            BlockBody::reward("miner", BLOCK_REWARD),
+        )
+    }
+
+    #[test]
+    fn blocks_must_carry_the_round_problem() {
+        let tip = ChainTip::genesis();
+        for kind in [PuzzleKind::MagicSquare, PuzzleKind::LatinSquare, PuzzleKind::Sudoku] {
+            let rules = IssueRules { kinds: vec![kind], ..IssueRules::default() };
+            let issued = rules.issue(&tip, 8);
+            assert_eq!(check_chain_position(&block_for(issued.clone(), &tip), &tip, &issued), Ok(()));
+
+            // 같은 시드로 만든 난이도 1 문제는 다시 만들어 봐도 맞지만, 라운드에서 낸 문제가 아니므로 거절
+            let downgraded = rules.issue(&tip, 1);
+            assert_eq!(rules.check_problem(&downgraded, &tip), Ok(()));
+            assert_eq!(
+                check_chain_position(&block_for(downgraded, &tip), &tip, &issued),
+                Err(BlockError::ProblemNotIssued { height: tip.next_height() })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn blocks_the_ledger_rejects_do_not_move_the_tip() {
+        let mut server = Server::new(ServerConfig::default(), mpsc::channel(1).0);
+        let round = server.start_round();
+        let tip = server.tip;
+
+        // 잔액이 없는 노드가 보내는 거래를 담은 블록: 원장에 반영할 수 없음
+        let mut block = block_for(round.problem.clone(), &tip);
+        block.body = BlockBody::with_fees("miner", BLOCK_REWARD, vec![Transaction::new("nobody", "shop", 5)]);
+        block.round_id = round.id;
+        block.seal();
+        server.set_new_block(block.clone());
+
+        let key = identity::generate_key(&mut rand::thread_rng());
+        let vote = ValidationResult {
+            is_valid: true,
+            node_id: identity::node_id(&key),
+            round_id: round.id,
+            block_hash: block.hash.clone(),
+            signature: identity::sign_vote(&key, round.id, &block.hash, true),
+        };
+        let (problem_tx, _) = broadcast::channel(4);
+        let (block_tx, _) = broadcast::channel(4);
+        server.process_consensus(vote, Arc::new(problem_tx), Arc::new(block_tx)).await;
+
+        // 체인 끝, 시각, 원장은 그대로이고 같은 높이에서 새 라운드
+        assert_eq!(server.tip, tip);
+        assert_eq!(server.tip_timestamp, chain::GENESIS_TIMESTAMP);
+        assert_eq!(server.ledger.height(), 0);
+        assert_eq!(server.ledger.balance("miner"), 0);
+        let next = server.round.as_ref().expect("new round");
+        assert_eq!((next.height, next.id), (tip.next_height(), round.id + 1));
+        assert!(server.current_block.is_none());
+    }
+}
@@ -2,13 +2,17 @@
 
 use axum::{Router};
 use axum::extract::Extension;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::task;
 use tower::{ServiceBuilder};
 use tower::limit::ConcurrencyLimitLayer;
 
+mod config;
+mod difficulty;
+mod mempool;
 mod models;
+mod pow;
 mod routes;
 mod handlers {
     pub mod my_broadcast;
@@ -17,9 +21,9 @@ mod handlers {
 #[tokio::main]
 async fn main() {
     // --------------------------
-    // 1) Problem 전용 채널 생성
+    // 1) 라운드 문제(Round) 전용 채널 생성
     // --------------------------
-    let (problem_tx, _problem_rx) = broadcast::channel::<models::Problem>(100);
+    let (problem_tx, _problem_rx) = broadcast::channel::<models::Round>(100);
     let problem_tx = Arc::new(problem_tx);
 
     // --------------------------
@@ -36,7 +40,9 @@ async fn main() {
     // ------------------------------------
     // 4) 서버(합의/거래 흐름 관리) 구조체 생성
     // ------------------------------------
-    let server = handlers::my_broadcast::Server::new(100, validation_tx.clone());
+    let config = config::ServerConfig::from_env();
+    println!("Server config: {:?} (protocol version {})", config, models::PROTOCOL_VERSION);
+    let server = handlers::my_broadcast::Server::new(config, validation_tx.clone());
     let server = Arc::new(Mutex::new(server));
 
     // ----------------------------
@@ -44,8 +50,24 @@ async fn main() {
     // ----------------------------
     let server_clone_for_validation = Arc::clone(&server);
     let problem_tx_for_validation = Arc::clone(&problem_tx);
+    let block_tx_for_validation = Arc::clone(&block_tx);
     task::spawn(async move {
-        handle_validation_results(server_clone_for_validation, validation_rx, problem_tx_for_validation).await;
+        handle_validation_results(
+            server_clone_for_validation,
+            validation_rx,
+            problem_tx_for_validation,
+            block_tx_for_validation,
+        )
+        .await;
+    });
+
+    // ----------------------------
+    // 5-2) 마감 시각이 지난 라운드를 만료시키고 새 문제를 내는 태스크
+    // ----------------------------
+    let server_clone_for_expiry = Arc::clone(&server);
+    let problem_tx_for_expiry = Arc::clone(&problem_tx);
+    task::spawn(async move {
+        expire_stale_rounds(server_clone_for_expiry, problem_tx_for_expiry).await;
     });
 
     // ----------------------------
@@ -64,6 +86,9 @@ async fn main() {
     .layer(Extension(Arc::clone(&problem_tx)))
     .layer(Extension(Arc::clone(&server)));
 
+    // 4x4 마방진 전체 목록은 첫 문제 생성 전에 미리 만들어 둠
+    task::spawn_blocking(blockchain_common::puzzles::magic_square::all_4x4_magic_squares);
+
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     println!("Server listening on {}", addr);
 
@@ -77,7 +102,8 @@ async fn main() {
 async fn handle_validation_results(
     server: Arc<Mutex<handlers::my_broadcast::Server>>,
     mut validation_rx: mpsc::Receiver<models::ValidationResult>,
-    problem_tx: Arc<broadcast::Sender<models::Problem>>,
+    problem_tx: Arc<broadcast::Sender<models::Round>>,
+    block_tx: Arc<broadcast::Sender<String>>,
 ) {
     while let Some(validation_result) = validation_rx.recv().await {
         println!(
@@ -86,7 +112,27 @@ async fn handle_validation_results(
         );
         // 서버 락 획득 후 합의 로직 처리
         let mut server_guard = server.lock().await;
-        server_guard.process_consensus(validation_result, Arc::clone(&problem_tx)).await;
+        server_guard
+            .process_consensus(validation_result, Arc::clone(&problem_tx), Arc::clone(&block_tx))
+            .await;
     }
     eprintln!("Validation receiver dropped");
 }
+
+// 라운드 마감 시각을 주기적으로 확인하는 비동기 함수
+async fn expire_stale_rounds(
+    server: Arc<Mutex<handlers::my_broadcast::Server>>,
+    problem_tx: Arc<broadcast::Sender<models::Round>>,
+) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        ticker.tick().await;
+        let reissued = server.lock().await.expire_round_if_due();
+        if let Some(round) = reissued {
+            match problem_tx.send(round) {
+                Ok(_) => println!("Problem re-issued after the round expired."),
+                Err(e) => eprintln!("Failed to broadcast re-issued problem: {}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// server/src/mempool.rs
+
+use std::cmp::Reverse;
+use std::collections::{HashSet, VecDeque};
+
+use blockchain_common::body::TransactionError;
+use blockchain_common::ledger::Ledger;
+
+use crate::models::Transaction;
+
+/// 아직 블록에 들어가지 않은 거래 (들어온 순서대로, 블록 템플릿은 수수료 순)
+///
+/// 같은 거래(해시가 같은 거래)는 한 번만 받고, `capacity`개가 차면 새 거래를 거절한다.
+/// 새 거래는 대기 중인 거래를 모두 반영한 원장에 반영할 수 있어야 한다
+/// (계정 원장: 대기 중인 금액을 뺀 잔액 안에서 대기 중인 거래 다음 nonce 로만, UTXO 원장: 대기 중인 거래가 쓰지 않은 출력만).
+pub struct Mempool {
+    entries: VecDeque<Transaction>,
+    hashes: HashSet<[u8; 32]>,
+    capacity: usize,
+}
+
+impl Mempool {
+    pub fn new(capacity: usize) -> Self {
+        Mempool { entries: VecDeque::new(), hashes: HashSet::new(), capacity }
+    }
+
+    /// 거래를 검사해 맨 뒤에 넣음 (서명은 `/transaction` 핸들러가 먼저 확인)
+    pub fn insert(&mut self, transaction: Transaction, ledger: &Ledger) -> Result<(), TransactionError> {
+        transaction.validate()?;
+        let hash = transaction.hash();
+        if self.hashes.contains(&hash) {
+            return Err(TransactionError::Duplicate { hash: hex::encode(hash) });
+        }
+        let reserved = transaction
+            .inputs
+            .iter()
+            .find(|input| self.entries.iter().any(|pending| pending.inputs.contains(input)));
+        if let Some(outpoint) = reserved {
+            return Err(TransactionError::DoubleSpend { outpoint: outpoint.clone() });
+        }
+        self.pending_state(ledger).apply_transaction(&transaction)?;
+        if self.entries.len() >= self.capacity {
+            return Err(TransactionError::MempoolFull { capacity: self.capacity });
+        }
+        self.hashes.insert(hash);
+        self.entries.push_back(transaction);
+        Ok(())
+    }
+
+    /// 다음 블록 템플릿에 담을 거래 최대 `limit`개 (블록 용량)
+    ///
+    /// 한 건씩, 지금까지 고른 거래를 반영한 원장에 반영할 수 있는 거래 중 수수료가 가장 큰 거래
+    /// (같으면 먼저 들어온 거래)를 고른다. 그래서 수수료가 커도 같은 계정의 앞 nonce 거래나
+    /// 자기가 쓰는 출력을 만드는 거래보다 앞에 오지 않는다.
+    pub fn template(&self, limit: usize, ledger: &Ledger) -> Vec<Transaction> {
+        let mut state = ledger.clone();
+        let mut candidates: Vec<&Transaction> = self.entries.iter().collect();
+        candidates.sort_by_key(|transaction| Reverse(transaction.fee));
+        let mut template = Vec::new();
+        while template.len() < limit {
+            // 반영에 실패한 거래는 원장을 바꾸지 않으므로 그대로 다음 후보를 시도
+            let Some(position) = candidates.iter().position(|transaction| state.apply_transaction(transaction).is_ok())
+            else {
+                break;
+            };
+            template.push(candidates.remove(position).clone());
+        }
+        template
+    }
+
+    /// 블록이 합의된 뒤 원장에 반영할 수 없게 된 거래(잔액 부족, 이미 쓰인 출력)를 빼고, 뺀 개수를 돌려줌
+    pub fn prune(&mut self, ledger: &Ledger) -> usize {
+        let mut state = ledger.clone();
+        let before = self.entries.len();
+        self.entries.retain(|transaction| state.apply_transaction(transaction).is_ok());
+        let kept: HashSet<[u8; 32]> = self.entries.iter().map(Transaction::hash).collect();
+        self.hashes = kept;
+        before - self.entries.len()
+    }
+
+    /// 블록에 들어간 거래를 빼고, 뺀 개수를 돌려줌
+    pub fn remove_included(&mut self, included: &[Transaction]) -> usize {
+        let included: HashSet<[u8; 32]> = included.iter().map(Transaction::hash).collect();
+        let before = self.entries.len();
+        self.entries.retain(|transaction| !included.contains(&transaction.hash()));
+        self.hashes.retain(|hash| !included.contains(hash));
+        before - self.entries.len()
+    }
+
+    /// 대기 중인 거래를 들어온 순서대로 모두 반영한 원장
+    fn pending_state(&self, ledger: &Ledger) -> Ledger {
+        let mut state = ledger.clone();
+        for pending in &self.entries {
+            // 반영할 수 없게 된 거래는 `prune`이 뺌
+            let _ = state.apply_transaction(pending);
+        }
+        state
+    }
+
+    /// 대기 중인 거래 전체 (들어온 순서)
+    pub fn pending(&self) -> Vec<Transaction> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockchain_common::chain::DEFAULT_CHAIN_ID;
+    use blockchain_common::ledger::LedgerModel;
+    use blockchain_common::protocol::OutPoint;
+    use blockchain_common::test_support::block;
+    use crate::models::Block;
+
+    fn tx(sender: &str, amount: u64) -> Transaction {
+        Transaction::new(sender, "shop", amount)
+    }
+
+    fn nth(sender: &str, amount: u64, nonce: u64) -> Transaction {
+        Transaction { nonce, ..tx(sender, amount) }
+    }
+
+    /// a, b, c 가 각각 보상 10 을 받은 원장
+    fn ledger(model: LedgerModel) -> Ledger {
+        let blocks: Vec<Block> = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, miner)| block(i as u64 + 1, miner, vec![]))
+            .collect();
+        Ledger::replay(model, DEFAULT_CHAIN_ID, &blocks).unwrap()
+    }
+
+    fn accounts() -> Ledger {
+        ledger(LedgerModel::Account)
+    }
+
+    #[test]
+    fn keeps_arrival_order_and_drops_duplicates() {
+        let accounts = accounts();
+        let mut mempool = Mempool::new(10);
+        assert_eq!(mempool.insert(tx("a", 1), &accounts), Ok(()));
+        assert_eq!(mempool.insert(tx("b", 2), &accounts), Ok(()));
+        assert!(matches!(mempool.insert(tx("a", 1), &accounts), Err(TransactionError::Duplicate { .. })));
+        assert_eq!(mempool.insert(tx("a", 0), &accounts), Err(TransactionError::ZeroAmount));
+        assert_eq!(mempool.pending(), vec![tx("a", 1), tx("b", 2)]);
+        assert_eq!(mempool.template(1, &accounts), vec![tx("a", 1)]);
+    }
+
+    #[test]
+    fn caps_entries_until_a_block_includes_them() {
+        let accounts = accounts();
+        let mut mempool = Mempool::new(2);
+        mempool.insert(tx("a", 1), &accounts).unwrap();
+        mempool.insert(tx("b", 1), &accounts).unwrap();
+        assert_eq!(mempool.insert(tx("c", 1), &accounts), Err(TransactionError::MempoolFull { capacity: 2 }));
+
+        assert_eq!(mempool.remove_included(&[tx("a", 1), tx("z", 9)]), 1);
+        assert_eq!(mempool.len(), 1);
+        assert_eq!(mempool.insert(tx("c", 1), &accounts), Ok(()));
+        assert_eq!(mempool.pending(), vec![tx("b", 1), tx("c", 1)]);
+    }
+
+    #[test]
+    fn senders_cannot_spend_more_than_they_have() {
+        let accounts = accounts();
+        let mut mempool = Mempool::new(10);
+        assert_eq!(mempool.insert(tx("a", 6), &accounts), Ok(()));
+        // 대기 중인 6 을 빼면 4 만 남음
+        assert_eq!(
+            mempool.insert(nth("a", 5, 1), &accounts),
+            Err(TransactionError::InsufficientFunds { account: "a".to_string(), balance: 4, amount: 5 })
+        );
+        assert_eq!(mempool.insert(tx("b", 10), &accounts), Ok(()));
+
+        // 합의된 블록에서 a 가 잔액을 다 쓰면 대기 중이던 a 의 거래는 빠짐
+        let mut spent = accounts.clone();
+        spent.apply_transaction(&tx("a", 10)).unwrap();
+        assert_eq!(mempool.template(10, &spent), vec![tx("b", 10)]);
+        assert_eq!(mempool.prune(&spent), 1);
+        assert_eq!(mempool.pending(), vec![tx("b", 10)]);
+        assert_eq!(mempool.insert(nth("a", 6, 1), &spent), Err(TransactionError::InsufficientFunds {
+            account: "a".to_string(),
+            balance: 0,
+            amount: 6,
+        }));
+    }
+
+    #[test]
+    fn nonces_follow_pending_transactions_and_chain_ids_must_match() {
+        let accounts = accounts();
+        let mut mempool = Mempool::new(10);
+        assert_eq!(
+            mempool.insert(nth("a", 1, 1), &accounts),
+            Err(TransactionError::BadNonce { account: "a".to_string(), expected: 0, actual: 1 })
+        );
+        assert_eq!(mempool.insert(nth("a", 1, 0), &accounts), Ok(()));
+        // 대기 중인 거래 다음 순번
+        assert_eq!(mempool.insert(nth("a", 1, 1), &accounts), Ok(()));
+        assert_eq!(
+            mempool.insert(Transaction { chain_id: DEFAULT_CHAIN_ID + 1, ..nth("a", 1, 2) }, &accounts),
+            Err(TransactionError::WrongChain { expected: DEFAULT_CHAIN_ID, actual: DEFAULT_CHAIN_ID + 1 })
+        );
+
+        // 블록에 들어간 거래를 다시 내면 nonce 가 맞지 않음
+        let mut committed = accounts.clone();
+        committed.apply_transaction(&nth("a", 1, 0)).unwrap();
+        mempool.remove_included(&[nth("a", 1, 0)]);
+        assert_eq!(
+            mempool.insert(nth("a", 1, 0), &committed),
+            Err(TransactionError::BadNonce { account: "a".to_string(), expected: 2, actual: 0 })
+        );
+        assert_eq!(mempool.template(10, &committed), vec![nth("a", 1, 1)]);
+    }
+
+    #[test]
+    fn templates_prefer_higher_fees_without_breaking_nonce_order() {
+        let accounts = accounts();
+        let mut mempool = Mempool::new(10);
+        let first = tx("a", 1);
+        let second = Transaction { fee: 5, ..nth("a", 1, 1) };
+        let generous = Transaction { fee: 2, ..tx("b", 1) };
+        let modest = Transaction { fee: 1, ..tx("c", 1) };
+        for transaction in [&first, &second, &generous, &modest] {
+            mempool.insert(transaction.clone(), &accounts).unwrap();
+        }
+        // 수수료 순이지만, a 의 두 번째 거래는 첫 거래 뒤에만 올 수 있음
+        assert_eq!(
+            mempool.template(10, &accounts),
+            vec![generous.clone(), modest.clone(), first.clone(), second.clone()]
+        );
+        // 블록 용량만큼만
+        assert_eq!(mempool.template(2, &accounts), vec![generous, modest]);
+        // 수수료까지 낼 수 있어야 함 (a 는 대기 중인 2 와 수수료 5 를 빼면 3)
+        assert_eq!(
+            mempool.insert(Transaction { fee: 1, ..nth("a", 3, 2) }, &accounts),
+            Err(TransactionError::InsufficientFunds { account: "a".to_string(), balance: 3, amount: 4 })
+        );
+    }
+
+    #[test]
+    fn utxo_inputs_are_reserved_by_pending_transactions() {
+        let ledger = ledger(LedgerModel::Utxo);
+        let Ledger::Utxo(set) = &ledger else { unreachable!() };
+        let coin: OutPoint = set.unspent("a").next().unwrap().0.clone();
+        let spend = |amount| Transaction { inputs: vec![coin.clone()], ..tx("a", amount) };
+
+        let mut mempool = Mempool::new(10);
+        assert_eq!(mempool.insert(tx("a", 1), &ledger), Err(TransactionError::WrongLedger { ledger: LedgerModel::Utxo }));
+        assert_eq!(mempool.insert(spend(4), &ledger), Ok(()));
+        // 같은 출력을 쓰는 다른 거래는 거절
+        assert_eq!(mempool.insert(spend(5), &ledger), Err(TransactionError::DoubleSpend { outpoint: coin.clone() }));
+        assert_eq!(mempool.template(10, &ledger), vec![spend(4)]);
+    }
+}
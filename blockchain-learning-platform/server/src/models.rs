@@ -1,43 +1,6 @@
 // server/src/models.rs
 
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Block {
-    pub index: u64,
-    pub timestamp: String,
-    pub problem: Problem,        // 블록에 포함된 문제
-    pub solution: Vec<Vec<u32>>, // 노드가 제출한 풀이
-    pub prev_solution: Vec<Vec<u32>>,
-    pub node_id: String,
-    pub data: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Problem {
-    pub matrix: Vec<Vec<u32>>, // 예: 마방진 문제용 2D 배열
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ValidationResult {
-    pub is_valid: bool,
-    pub node_id: String,
-}
-
-// ------------------------------
-// 새로 추가: Transaction 구조체
-// ------------------------------
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transaction {
-    pub sender_id: String,
-    pub receiver_id: String,
-    pub amount: u64,
-}
-
-#[derive(Debug, Clone,Serialize, Deserialize)]
-#[serde(tag = "type", content = "data")]
-pub enum ServerMessage {
-    Problem(Problem),
-    #[serde(rename = "block")]
-    Block(Block),
-}
+// 주고받는 타입은 클라이언트와 같은 정의를 씀 (`blockchain_common::protocol`)
+pub use blockchain_common::protocol::{
+    Block, Problem, Round, RoundError, ServerMessage, Transaction, ValidationResult, VoteError, PROTOCOL_VERSION,
+};
@@ -0,0 +1,67 @@
+// server/src/pow.rs
+
+use blockchain_common::pow;
+use blockchain_common::puzzles::SolutionError;
+
+use crate::models::Block;
+
+/// 블록이 라운드 목표(`target_bits`)를 겨냥했고, 헤더 해시가 그 목표를 만족하는지 검사
+pub fn verify_work(block: &Block, target_bits: u32) -> Result<(), SolutionError> {
+    pow::verify_work(&block.header(), target_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockchain_common::chain::{GENESIS_PREV_HASH, GENESIS_TIMESTAMP};
+    use blockchain_common::pow::{leading_zero_bits, work_problem};
+    use blockchain_common::protocol::{BlockBody, BLOCK_REWARD};
+
+    fn block(target_bits: u32) -> Block {
+        Block {
+            index: 1,
+            prev_hash: GENESIS_PREV_HASH.to_string(),
+            hash: String::new(),
+            timestamp: GENESIS_TIMESTAMP,
+            problem: work_problem(target_bits),
+            solution: Vec::new(),
+            prev_solution: Vec::new(),
+            node_id: "miner".to_string(),
+            merkle_root: String::new(),
+            body: BlockBody::reward("miner", BLOCK_REWARD),
+            difficulty: target_bits,
+            nonce: 0,
+            difficulty_target: target_bits,
+            round_id: 1,
+            signature: String::new(),
+        }
+    }
+
+    fn mine(mut block: Block) -> Block {
+        while leading_zero_bits(&block.compute_hash()) < block.difficulty_target {
+            block.nonce += 1;
+        }
+        block
+    }
+
+    #[test]
+    fn accepts_a_mined_block() {
+        let mined = mine(block(12));
+        assert_eq!(verify_work(&mined, 12), Ok(()));
+    }
+
+    #[test]
+    fn rejects_tampered_or_mistargeted_blocks() {
+        let mined = mine(block(12));
+
+        let mut tampered = mined.clone();
+        tampered.body.coinbase.amount = 1000;
+        tampered.seal();
+        assert!(matches!(verify_work(&tampered, 12), Err(SolutionError::InsufficientWork { .. })));
+
+        assert_eq!(
+            verify_work(&mined, 16),
+            Err(SolutionError::TargetMismatch { expected: 16, actual: 12 })
+        );
+    }
+}
@@ -5,13 +5,11 @@ use axum::{
     routing::{post, get}, 
     extract::Extension, 
     Json, 
-    response::IntoResponse,
 };
-use hyper::StatusCode;
 use std::sync::Arc;
 use tokio::sync::{broadcast::Sender, mpsc::Sender as MpscSender, Mutex};
 
-use crate::models::{Block, Problem, ValidationResult, Transaction};
+use crate::models::{Block, Round, ValidationResult, Transaction};
 use crate::handlers::my_broadcast::{self, Server};
 
 use axum::routing::get as axum_get;
@@ -19,7 +17,7 @@ use crate::handlers::my_broadcast::handle_websocket;
 
 pub fn create_routes(
     tx: Arc<Sender<String>>,
-    problem_tx: Arc<Sender<Problem>>,
+    problem_tx: Arc<Sender<Round>>,
     validation_sender: MpscSender<ValidationResult>, // 동일한 validation_sender 사용
     server: Arc<Mutex<Server>>, // 서버 상태 접근용
 ) -> Router {
@@ -43,8 +41,7 @@ pub fn create_routes(
                         Extension(Arc::clone(&tx)),
                         Extension(server_clone.clone()),
                     )
-                    .await;
-                    "Block submitted"
+                    .await
                 }
             }),
         )
@@ -55,16 +52,55 @@ pub fn create_routes(
             "/submit_validation",
             post({
                 let validation_sender = validation_sender.clone();
+                let server_clone = Arc::clone(&server);
                 move |Json(validation_result): Json<ValidationResult>| async move {
-                    if let Err(e) = validation_sender.send(validation_result).await {
-                        eprintln!("Failed to send validation result: {}", e);
-                        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to submit validation result");
-                    }
-                    (StatusCode::OK, "Validation result submitted successfully")
+                    my_broadcast::handle_validation_submission(
+                        Json(validation_result),
+                        Extension(validation_sender.clone()),
+                        Extension(server_clone.clone()),
+                    )
+                    .await
                 }
             }),
         )
 
+        // 거래 제출: 검사 후 멤풀에 넣고 멤풀 변경을 브로드캐스트
+        .route(
+            "/transaction",
+            post({
+                let tx = Arc::clone(&tx);
+                let server_clone = Arc::clone(&server);
+                move |Json(transaction): Json<Transaction>| async move {
+                    my_broadcast::handle_transaction_submission(
+                        Json(transaction),
+                        Extension(Arc::clone(&tx)),
+                        Extension(server_clone.clone()),
+                    )
+                    .await
+                }
+            }),
+        )
+
+        // 잔액 조회: 합의된 체인을 재생한 잔액
+        .route(
+            "/balance/:node_id",
+            get({
+                let server_clone = Arc::clone(&server);
+                move |path: axum::extract::Path<String>| async move {
+                    my_broadcast::handle_balance(path, Extension(server_clone.clone())).await
+                }
+            }),
+        )
+
+        // 발행량 조회: 지금까지의 총 발행량과 다음 반감 높이
+        .route(
+            "/supply",
+            get({
+                let server_clone = Arc::clone(&server);
+                move || async move { my_broadcast::handle_supply(Extension(server_clone.clone())).await }
+            }),
+        )
+
         // WebSocket 라우트 추가
         .route(
             "/ws",